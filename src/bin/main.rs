@@ -1,125 +1,527 @@
 use std::path::PathBuf;
-use std::time::SystemTime;
+use std::sync::Arc;
+use std::time::Duration;
 
-use anglerfish_iterator_rs::config::load_config;
-use anglerfish_iterator_rs::helper::duration::duration_ms_to_minutes;
+use anglerfish_iterator_rs::config::{Network, NetworkName, load_config};
+use anglerfish_iterator_rs::health::Health;
+use anglerfish_iterator_rs::helper::duration::{
+    current_timestamp_ms, duration_ms_to_minutes, format_duration_ms, format_timestamp_ms,
+};
+use anglerfish_iterator_rs::helper::network::build_sui_client;
+use anglerfish_iterator_rs::journal::Journal;
+use anglerfish_iterator_rs::leader::LeaderElector;
+use anglerfish_iterator_rs::metrics::Metrics;
+use anglerfish_iterator_rs::notifier::Alerter;
 use anglerfish_iterator_rs::protocols::anglerfish::client::AnglerfishClient;
-use anyhow::Result;
+use anglerfish_iterator_rs::protocols::anglerfish::confirmation::{
+    AnglerfishConfirmation, TransitionOutcome,
+};
+use anglerfish_iterator_rs::protocols::anglerfish::engine::{Action, Operation, PhaseIterator};
+use anglerfish_iterator_rs::protocols::anglerfish::retry::{ErrorClass, RetryPolicy};
+use anglerfish_iterator_rs::protocols::anglerfish::scheduler::{Schedule, Scheduler};
+use anglerfish_iterator_rs::protocols::anglerfish::validator::AnglerfishConfigValidator;
+use anglerfish_iterator_rs::server::{ServerState, serve};
+use anyhow::{Context, Result, bail};
 
-use anglerfish_iterator_rs::helper::wallet::retrieve_wallet;
-use anglerfish_iterator_rs::protocols::anglerfish::types::phase_info::Phase;
-
-use sui_sdk::SuiClientBuilder;
+use anglerfish_iterator_rs::signer::load_signer;
 
+use clap::{Parser, Subcommand, ValueEnum};
+use sui_sdk::types::base_types::SuiAddress;
 use tokio::time::sleep;
+use tracing::{Span, error, info, warn};
+use tracing_subscriber::EnvFilter;
+
+/// Iterates the phases of the Anglerfish protocol.
+#[derive(Parser)]
+#[command(name = "anglerfish-iterator", version)]
+struct Cli {
+    /// Path to the configuration file.
+    #[arg(long, default_value = "anglerfish_iterator_config.toml")]
+    config: PathBuf,
+
+    /// Network to connect to (mainnet, testnet, devnet or localnet), overrides
+    /// the `[network]` section of the configuration.
+    #[arg(long)]
+    network: Option<NetworkName>,
+
+    /// Address signing the transactions, overrides `address` in the `[signer]`
+    /// section of the configuration.
+    #[arg(long)]
+    address: Option<SuiAddress>,
+
+    /// Log output format. The log level is filtered with `RUST_LOG`, e.g.
+    /// `RUST_LOG=anglerfish_iterator_rs=debug`, and defaults to `info`.
+    #[arg(long, value_enum, default_value_t = LogFormat::Pretty)]
+    log_format: LogFormat,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LogFormat {
+    /// Human readable lines.
+    Pretty,
+    /// One JSON object per line, for log aggregators.
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the current phase information.
+    Status,
+    /// Check the configuration against the on-chain state.
+    Validate,
+    /// End the liquidity providing or ticketing phase.
+    NextEntry,
+    /// Draw the winner of the current round.
+    Draw,
+    /// Distribute the prize of the current round.
+    Distribute,
+    /// Settle the current round and start a new one.
+    StartNewRound,
+    /// Continuously advance the phases (default).
+    Run,
+    /// Advance the current phase once if it is due, then exit.
+    RunOnce,
+    /// Send a test alert to the configured webhooks.
+    TestAlert,
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let wallet = retrieve_wallet()?;
+    let cli = Cli::parse();
+    init_logging(cli.log_format);
 
     // Load the configuration file
-    let config = load_config(PathBuf::from("anglerfish_iterator_config.toml"))?;
+    let mut config = load_config(&cli.config)
+        .with_context(|| format!("Failed to load config from {:?}", cli.config))?;
+    if let Some(name) = cli.network {
+        config.network = Network {
+            name,
+            request_timeout_secs: config.network.request_timeout_secs,
+            ..Default::default()
+        };
+    }
+    if let Some(address) = cli.address {
+        config.signer.address = Some(address);
+    }
+    let command = cli.command.unwrap_or(Command::Run);
+    // reading the phase or sending a test alert does not need a key
+    let signer = match command {
+        Command::Status | Command::TestAlert => None,
+        _ => Some(load_signer(&config.signer)?),
+    };
+    let health = Arc::new(Health::new(Duration::from_secs(
+        config.server.liveness_timeout_secs,
+    )));
+    if let Some(signer) = &signer {
+        health.signer_loaded(signer.address());
+    }
+    let scheduler = Scheduler::from_config(&config.scheduler);
+    let mut retry_policy = RetryPolicy::new(config.retry.clone());
+    let phase_iterator = PhaseIterator::new();
+    let leader = Arc::new(LeaderElector::from_config(&config.leader)?);
+    let alerter = Alerter::from_config(&config.alerts)?;
 
     // Instantiate the Anglerfish client
-    let sui_client = SuiClientBuilder::default().build_testnet().await?;
-    let anglerfish_client = AnglerfishClient::new(sui_client, wallet, config);
-
-    loop {
-        let phase_info = anglerfish_client.get_phase_info().await;
-        if phase_info.is_err() {
-            sleep(std::time::Duration::from_secs(30)).await;
-            continue;
+    let sui_client = build_sui_client(&config.network).await?;
+    let server_config = config.server.clone();
+    let journal = match config.journal.enabled {
+        true => Some(Journal::open(&config.journal.path)?),
+        false => None,
+    };
+    let mut anglerfish_client = AnglerfishClient::new(sui_client, config);
+    if let Some(signer) = signer {
+        anglerfish_client = anglerfish_client.with_signer(signer);
+    }
+    if let Some(journal) = journal {
+        anglerfish_client = anglerfish_client.with_journal(journal);
+    }
+    anglerfish_client = anglerfish_client.with_leader(leader.clone());
+    if server_config.enabled {
+        let metrics = Arc::new(Metrics::new()?);
+        anglerfish_client = anglerfish_client.with_metrics(metrics.clone());
+        let state = ServerState {
+            metrics,
+            health: health.clone(),
+            sui_client: anglerfish_client.sui_client().clone(),
+            min_balance_mist: server_config.min_balance_mist,
+        };
+        tokio::spawn(async move {
+            if let Err(e) = serve(server_config.listen_address, state).await {
+                error!(error = %e, "Monitoring server stopped");
+            }
+        });
+    }
+
+    let renewal = match command {
+        Command::Status | Command::Validate | Command::TestAlert => None,
+        Command::Run => {
+            anglerfish_client.validate().await?;
+            health.config_validated();
+            if let Err(e) = leader.acquire().await {
+                warn!(
+                    error = format!("{:#}", e),
+                    "Failed to acquire the leader lease"
+                );
+            }
+            leader.spawn_renewal()
+        }
+        _ => {
+            anglerfish_client.validate().await?;
+            health.config_validated();
+            // one-off commands submit right away, so the lease must be free
+            take_lead(&leader).await?;
+            leader.spawn_renewal()
         }
+    };
 
-        let phase_info = phase_info.unwrap();
-        let start = SystemTime::now();
-        let current_timestamp_ms = start.duration_since(SystemTime::UNIX_EPOCH)?.as_millis() as u64;
-
-        let current_phase = phase_info.current_phase.clone();
-        match current_phase {
-            Phase::Uninitialized => {}
-            Phase::LiquidityProviding => {
-                let expected_end_at =
-                    phase_info.current_phase_at + phase_info.durations.liquidity_providing_duration;
-                if current_timestamp_ms > expected_end_at {
-                    println!("Liquidity providing phase is over. Executing next entry...");
-                    let res = anglerfish_client.execute_next_entry().await;
-                    match res {
-                        Err(e) => {
-                            println!("Error executing next entry: {}", e);
-                        }
-                        Ok(digest) => {
-                            println!("Next entry transaction digest: {}", digest);
-                        }
-                    }
-                } else {
-                    let remaining_time = expected_end_at - current_timestamp_ms;
-                    println!(
-                        "Liquidity providing: Remaining time: ~{} m",
-                        duration_ms_to_minutes(remaining_time)
-                    );
+    match command {
+        Command::Status => print_status(&anglerfish_client, &phase_iterator).await?,
+        Command::Validate => {
+            anglerfish_client.validate().await?;
+            info!("Configuration is valid");
+        }
+        Command::TestAlert => {
+            alerter.test().await?;
+            info!("Test alert sent");
+        }
+        Command::NextEntry => {
+            run_operation(&anglerfish_client, &mut retry_policy, Operation::NextEntry).await?
+        }
+        Command::Draw => {
+            run_operation(&anglerfish_client, &mut retry_policy, Operation::Draw).await?
+        }
+        Command::Distribute => {
+            run_operation(&anglerfish_client, &mut retry_policy, Operation::Distribute).await?
+        }
+        Command::StartNewRound => {
+            run_operation(
+                &anglerfish_client,
+                &mut retry_policy,
+                Operation::StartNewRound,
+            )
+            .await?
+        }
+        Command::Run => loop {
+            health.tick();
+            if !leader.is_leader() {
+                match leader.current_lease() {
+                    Some(lease) => info!(
+                        holder = %lease.holder,
+                        expires_at = %format_timestamp_ms(lease.expires_at_ms),
+                        "Standby: leader lease held by another replica"
+                    ),
+                    None => info!("Standby: waiting for the leader lease"),
                 }
+                health.action("standby");
+                health.sleeping(leader.renew_interval());
+                sleep(leader.renew_interval()).await;
+                continue;
             }
-            Phase::Ticketing => {
-                let expected_end_at =
-                    phase_info.current_phase_at + phase_info.durations.ticketing_duration;
-                if current_timestamp_ms > expected_end_at {
-                    println!("Ticketing phase is over. Executing next entry...");
-                    let res = anglerfish_client.execute_next_entry().await;
-                    match res {
-                        Err(e) => {
-                            println!("Error executing next entry: {}", e);
-                        }
-                        Ok(digest) => {
-                            println!("Next entry transaction digest: {}", digest);
-                        }
-                    }
-                } else {
-                    let remaining_time = expected_end_at - current_timestamp_ms;
-                    println!(
-                        "Ticketing: Remaining time: ~{} m",
-                        duration_ms_to_minutes(remaining_time)
-                    );
+
+            match tick(
+                &anglerfish_client,
+                &phase_iterator,
+                &scheduler,
+                &leader,
+                &health,
+                &alerter,
+            )
+            .await
+            {
+                Ok(wait) => {
+                    retry_policy.on_success();
+                    alerter.tick_succeeded();
+                    health.sleeping(wait);
+                    sleep(wait).await
                 }
-            }
-            Phase::Drawing => {
-                println!("Drawing: execute draw");
-                let res = anglerfish_client.execute_draw().await;
-                match res {
-                    Err(e) => {
-                        println!("Error drawing: {}", e);
-                    }
-                    Ok(digest) => {
-                        println!("Drawing transaction digest: {}", digest);
-                    }
+                Err(e) => {
+                    let class = ErrorClass::classify_any(&e);
+                    let delay = retry_policy.on_failure(&class);
+                    error!(
+                        class = %class,
+                        error = format!("{:#}", e),
+                        retry_in = ?delay,
+                        "Tick failed"
+                    );
+                    health.error(format!("{}: {:#}", class, e));
+                    alerter.tick_failed(&format!("{:#}", e)).await;
+                    health.sleeping(delay);
+                    sleep(delay).await;
                 }
             }
-            Phase::Distributing => {
-                println!("Distributing: Execute distribute");
-                let res = anglerfish_client.execute_distribute().await;
-                match res {
-                    Err(e) => {
-                        println!("Error distributing: {}", e);
-                    }
-                    Ok(digest) => {
-                        println!("Distributing transaction digest: {}", digest);
-                    }
-                }
+        },
+        Command::RunOnce => {
+            tick(
+                &anglerfish_client,
+                &phase_iterator,
+                &scheduler,
+                &leader,
+                &health,
+                &alerter,
+            )
+            .await?;
+        }
+    }
+
+    if let Some(renewal) = renewal {
+        renewal.abort();
+    }
+    if let Err(e) = leader.release().await {
+        warn!(
+            error = format!("{:#}", e),
+            "Failed to release the leader lease"
+        );
+    }
+
+    Ok(())
+}
+
+/// Acquires the leader lease, failing if another replica holds it.
+async fn take_lead(leader: &LeaderElector) -> Result<()> {
+    if leader.acquire().await? {
+        return Ok(());
+    }
+    match leader.current_lease() {
+        Some(lease) => bail!(
+            "The leader lease is held by {} until {}",
+            lease.holder,
+            format_timestamp_ms(lease.expires_at_ms)
+        ),
+        None => bail!("The leader lease could not be acquired"),
+    }
+}
+
+/// Reads the current phase and advances it if it is due.
+/// Returns how long to wait before the next tick.
+#[tracing::instrument(skip_all, fields(round, phase))]
+async fn tick(
+    anglerfish_client: &AnglerfishClient,
+    phase_iterator: &PhaseIterator,
+    scheduler: &Scheduler,
+    leader: &LeaderElector,
+    health: &Health,
+    alerter: &Alerter,
+) -> Result<Duration> {
+    // a transaction submitted before a crash may still advance the phase
+    resolve_pending(anglerfish_client)
+        .await
+        .context("resolving journaled transactions")?;
+
+    let phase_info = anglerfish_client
+        .get_phase_info()
+        .await
+        .context("fetching phase info")?;
+
+    health.phase_info(&phase_info);
+    Span::current()
+        .record("round", phase_info.current_round_number)
+        .record("phase", tracing::field::debug(phase_info.current_phase));
+
+    let current_timestamp_ms = current_timestamp_ms();
+    alerter.check_phase(&phase_info, current_timestamp_ms).await;
+    if alerter.watches_balance() {
+        match anglerfish_client.get_signer_balance().await {
+            Ok(balance) => alerter.check_balance(balance).await,
+            Err(e) => warn!(error = %e, "Failed to fetch the signer balance"),
+        }
+    }
+
+    let action = phase_iterator.next_action(&phase_info, current_timestamp_ms);
+    health.action(format!("{:?}", action));
+    let operation = match scheduler.schedule(&action, current_timestamp_ms) {
+        Schedule::RecheckIn(wait) => {
+            match action {
+                Action::WaitUntil(expected_end_at) => info!(
+                    action = ?action,
+                    remaining_minutes =
+                        duration_ms_to_minutes(expected_end_at - current_timestamp_ms),
+                    "Waiting for the phase deadline"
+                ),
+                _ => info!(action = ?action, "Waiting"),
             }
-            Phase::Settling => {
-                println!("Settling: Iterate to next round");
-                let res = anglerfish_client.execute_start_new_round().await;
-                match res {
-                    Err(e) => {
-                        println!("Error starting new round: {}", e);
-                    }
-                    Ok(digest) => {
-                        println!("Starting new round transaction digest: {}", digest);
-                    }
-                }
+            return Ok(wait);
+        }
+        Schedule::FireIn(wait) => {
+            info!(
+                action = ?action,
+                wait_ms = wait.as_millis() as u64,
+                "Phase ends soon, waiting for the deadline"
+            );
+            sleep(wait).await;
+            match phase_iterator.next_action(&phase_info, current_timestamp_ms()) {
+                Action::Execute(operation) => operation,
+                _ => return Ok(Duration::ZERO),
             }
         }
+        Schedule::Due => match action {
+            Action::Execute(operation) => operation,
+            _ => return Ok(Duration::ZERO),
+        },
+    };
 
-        sleep(std::time::Duration::from_secs(30)).await;
+    // waiting for the deadline may have eaten into the lease
+    if !leader.is_leader() {
+        warn!(action = %operation, "Leader lease expiring, not submitting");
+        return Ok(Duration::ZERO);
     }
+
+    info!(action = %operation, "Executing");
+    let result = anglerfish_client
+        .execute_operation(operation)
+        .await
+        .with_context(|| format!("executing {}", operation))?;
+    let outcome = anglerfish_client
+        .confirm_transition(&phase_info, operation, result)
+        .await
+        .with_context(|| format!("confirming {}", operation))?;
+    report_outcome(operation, &outcome);
+    match &outcome {
+        TransitionOutcome::Confirmed { phase_info, .. }
+            if operation == Operation::StartNewRound =>
+        {
+            alerter.round_completed(phase_info).await
+        }
+        _ => {}
+    }
+    health.action(format!(
+        "{} {} ({})",
+        operation,
+        match outcome.is_confirmed() {
+            true => "confirmed",
+            false => "not observed",
+        },
+        outcome.result().digest
+    ));
+
+    // The next phase is read right away after a confirmed transition. An
+    // unobserved one is re-checked later rather than submitted again at once.
+    if outcome.is_confirmed() {
+        Ok(Duration::ZERO)
+    } else {
+        Ok(scheduler.poll_interval())
+    }
+}
+
+/// Submits a single operation, retrying failures, and confirms the resulting
+/// transition.
+async fn run_operation(
+    anglerfish_client: &AnglerfishClient,
+    retry_policy: &mut RetryPolicy,
+    operation: Operation,
+) -> Result<()> {
+    resolve_pending(anglerfish_client).await?;
+    let phase_info = anglerfish_client.get_phase_info().await?;
+    // a submission with an unknown outcome is not retried, see
+    // `RetryPolicy::retry`
+    let result = retry_policy
+        .retry(|| anglerfish_client.execute_operation(operation))
+        .await?;
+    let outcome = anglerfish_client
+        .confirm_transition(&phase_info, operation, result)
+        .await?;
+    report_outcome(operation, &outcome);
+    Ok(())
+}
+
+/// Resolves the transactions journaled as submitted without a known outcome.
+async fn resolve_pending(anglerfish_client: &AnglerfishClient) -> Result<()> {
+    if let Some(journal) = anglerfish_client.journal() {
+        journal.resolve_pending(anglerfish_client).await?;
+    }
+    Ok(())
+}
+
+fn report_outcome(operation: Operation, outcome: &TransitionOutcome) {
+    let digest = outcome.result().digest;
+    match outcome {
+        TransitionOutcome::Confirmed {
+            checkpoint,
+            phase_info,
+            ..
+        } => info!(
+            action = %operation,
+            %digest,
+            checkpoint,
+            new_round = phase_info.current_round_number,
+            new_phase = ?phase_info.current_phase,
+            "Transition confirmed"
+        ),
+        TransitionOutcome::NotObserved {
+            checkpoint,
+            expected,
+            phase_info,
+            ..
+        } => warn!(
+            action = %operation,
+            %digest,
+            checkpoint,
+            expected = %expected,
+            found_round = phase_info.current_round_number,
+            found_phase = ?phase_info.current_phase,
+            "Transition not observed"
+        ),
+    }
+}
+
+/// Installs the global subscriber, filtered with `RUST_LOG`.
+fn init_logging(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let subscriber = tracing_subscriber::fmt().with_env_filter(filter);
+    match format {
+        LogFormat::Pretty => subscriber.init(),
+        LogFormat::Json => subscriber.json().flatten_event(true).init(),
+    }
+}
+
+async fn print_status(
+    anglerfish_client: &AnglerfishClient,
+    phase_iterator: &PhaseIterator,
+) -> Result<()> {
+    let phase_info = anglerfish_client.get_phase_info().await?;
+    let current_timestamp_ms = current_timestamp_ms();
+
+    println!("Round number:        {}", phase_info.current_round_number);
+    println!("Current phase:       {:?}", phase_info.current_phase);
+    println!(
+        "Phase started at:    {}",
+        format_timestamp_ms(phase_info.current_phase_at)
+    );
+    match phase_info.current_phase_deadline() {
+        Some(deadline) if current_timestamp_ms <= deadline => println!(
+            "Phase ends at:       {} (in {})",
+            format_timestamp_ms(deadline),
+            format_duration_ms(deadline - current_timestamp_ms)
+        ),
+        Some(deadline) => println!(
+            "Phase ended at:      {} (overdue by {})",
+            format_timestamp_ms(deadline),
+            format_duration_ms(current_timestamp_ms - deadline)
+        ),
+        None => println!("Phase ends at:       -"),
+    }
+    println!(
+        "Liquidity providing: {}",
+        format_duration_ms(phase_info.durations.liquidity_providing_duration)
+    );
+    println!(
+        "Ticketing:           {}",
+        format_duration_ms(phase_info.durations.ticketing_duration)
+    );
+    if phase_info.last_drawing_timestamp_ms == 0 {
+        println!("Last drawing at:     never");
+    } else {
+        println!(
+            "Last drawing at:     {}",
+            format_timestamp_ms(phase_info.last_drawing_timestamp_ms)
+        );
+    }
+    println!(
+        "Next action:         {:?}",
+        phase_iterator.next_action(&phase_info, current_timestamp_ms)
+    );
+
+    Ok(())
 }
@@ -1,25 +1,57 @@
-use std::{fs, path::Path};
+use std::{
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use anyhow::Result;
 use serde::Deserialize;
+use sui_sdk::{
+    SUI_DEVNET_URL, SUI_LOCAL_NETWORK_URL, SUI_MAINNET_URL, SUI_TESTNET_URL,
+    types::{
+        TypeTag,
+        base_types::{ObjectID, SuiAddress},
+    },
+};
+
+use crate::{
+    error::{AnglerfishError, Result},
+    protocols::anglerfish::{engine::Operation, ids},
+    signer::remote::REMOTE_SIGNER_TIMEOUT,
+};
 
 // Define a struct to hold your configuration data.  Use `serde` attributes
 // to specify how to deserialize the TOML data into this struct.
+// Object ids are parsed once here, so a malformed id fails at load time.
 #[derive(Debug, Deserialize)]
+#[serde(try_from = "RawConfig")]
 pub struct Config {
-    pub package_id: String,
+    pub package_id: ObjectID,
+    /// Package which first published the Anglerfish types, `package_id` unless
+    /// the package has been upgraded. Object and event types are keyed by it.
+    pub original_package_id: ObjectID,
     pub objects: Objects,
     pub pool: Pool,
     pub iterator: Iterator,
+    pub network: Network,
+    pub signer: Signer,
+    pub gas: Gas,
+    pub retry: Retry,
+    pub scheduler: Scheduler,
+    pub confirmation: Confirmation,
+    pub leader: Leader,
+    pub journal: Journal,
+    pub server: Server,
+    pub alerts: Alerts,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Objects {
-    pub phase_info_id: String,
-    pub round_registry_id: String,
-    pub pool_registry_id: String,
-    pub prize_pool_id: String,
-    pub lounge_registry_id: String,
+    pub phase_info_id: ObjectID,
+    pub round_registry_id: ObjectID,
+    pub pool_registry_id: ObjectID,
+    pub prize_pool_id: ObjectID,
+    pub lounge_registry_id: ObjectID,
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,7 +61,523 @@ pub struct Pool {
 
 #[derive(Debug, Deserialize)]
 pub struct Iterator {
-    pub cap_id: String,
+    pub cap_id: ObjectID,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignerBackend {
+    /// Key of the Sui file based keystore.
+    #[default]
+    Keystore,
+    /// `suiprivkey` key read from an environment variable.
+    Env,
+    /// Key read from a standalone key file.
+    File,
+    /// Remote signing service.
+    Remote,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Signer {
+    pub backend: SignerBackend,
+    /// Address signing the iterator transactions. Selects the keystore key
+    /// (defaults to the active address of the Sui client config), is checked
+    /// against the env and file keys, and is required by the remote signer.
+    pub address: Option<SuiAddress>,
+    /// Keystore path, defaults to the one of the Sui client config directory.
+    pub keystore_path: Option<PathBuf>,
+    /// Environment variable holding the key of the env signer.
+    pub env_var: String,
+    /// Key file of the file signer.
+    pub key_path: Option<PathBuf>,
+    /// Base url of the remote signer.
+    pub url: Option<String>,
+    /// Environment variable holding the bearer token of the remote signer.
+    pub auth_token_env: Option<String>,
+}
+
+impl Default for Signer {
+    fn default() -> Self {
+        Signer {
+            backend: SignerBackend::Keystore,
+            address: None,
+            keystore_path: None,
+            env_var: String::from("ANGLERFISH_SIGNER_KEY"),
+            key_path: None,
+            url: None,
+            auth_token_env: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaseBackend {
+    /// No coordination, the instance always submits.
+    #[default]
+    None,
+    /// Lease record in a locked local file, for replicas sharing a host or volume.
+    File,
+    /// Lease held by an HTTP lease service.
+    Http,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Leader {
+    pub backend: LeaseBackend,
+    /// Name of the lease, shared by all the replicas of one iterator.
+    pub lease_name: String,
+    /// Identifier of this replica, defaults to `<hostname>-<pid>`.
+    pub holder_id: Option<String>,
+    /// Lifetime of an acquired lease, in seconds. It must outlast a renew
+    /// interval, a signing request and a confirmation wait, which can all
+    /// happen between the last lease check and a submission.
+    pub ttl_secs: u64,
+    /// Interval between two lease renewals or acquisition attempts, in seconds.
+    pub renew_interval_secs: u64,
+    /// Lease file of the file backend.
+    pub path: PathBuf,
+    /// Base url of the HTTP lease service.
+    pub url: Option<String>,
+    /// Environment variable holding the bearer token of the HTTP lease service.
+    pub auth_token_env: Option<String>,
+}
+
+impl Default for Leader {
+    fn default() -> Self {
+        Leader {
+            backend: LeaseBackend::None,
+            lease_name: String::from("anglerfish-iterator"),
+            holder_id: None,
+            ttl_secs: 90,
+            renew_interval_secs: 10,
+            path: PathBuf::from("anglerfish_iterator.lease"),
+            url: None,
+            auth_token_env: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Journal {
+    pub enabled: bool,
+    /// Append-only JSONL file recording every attempted transaction.
+    pub path: PathBuf,
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Journal {
+            enabled: true,
+            path: PathBuf::from("anglerfish_iterator_journal.jsonl"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Server {
+    /// Serves the monitoring endpoints (`/metrics`, `/healthz`, `/readyz`
+    /// and `/status`).
+    pub enabled: bool,
+    pub listen_address: SocketAddr,
+    /// `/healthz` fails once the loop has not ticked for this long, not
+    /// counting its sleeps, in seconds.
+    pub liveness_timeout_secs: u64,
+    /// `/readyz` fails while the signer balance is below this, in MIST.
+    pub min_balance_mist: u64,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Server {
+            enabled: false,
+            listen_address: SocketAddr::from(([127, 0, 0, 1], 9184)),
+            liveness_timeout_secs: 300,
+            min_balance_mist: 500_000_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    /// JSON body with the alert fields.
+    Generic,
+    /// Slack incoming webhook payload.
+    Slack,
+    /// Discord webhook payload.
+    Discord,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Webhook {
+    pub kind: WebhookKind,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Alerts {
+    /// Webhooks receiving every alert.
+    pub webhooks: Vec<Webhook>,
+    /// Minimum time between two alerts of the same event, in seconds.
+    pub rate_limit_secs: u64,
+    /// Consecutive failed ticks before alerting.
+    pub failure_threshold: u32,
+    /// Alert once the current phase is due for this long, in seconds.
+    pub overdue_threshold_secs: u64,
+    /// Alert while the signer balance is below this, in MIST. Disabled when unset.
+    pub min_balance_mist: Option<u64>,
+    /// Also notify when a round is completed.
+    pub round_completed: bool,
+}
+
+impl Default for Alerts {
+    fn default() -> Self {
+        Alerts {
+            webhooks: vec![],
+            rate_limit_secs: 900,
+            failure_threshold: 3,
+            overdue_threshold_secs: 600,
+            min_balance_mist: None,
+            round_completed: false,
+        }
+    }
+}
+
+/// Built-in deployments which provide defaults for `package_id`, `[objects]`
+/// and `[pool]`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    Testnet,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkName {
+    Mainnet,
+    Testnet,
+    Devnet,
+    Localnet,
+}
+
+impl FromStr for NetworkName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mainnet" => Ok(NetworkName::Mainnet),
+            "testnet" => Ok(NetworkName::Testnet),
+            "devnet" => Ok(NetworkName::Devnet),
+            "localnet" => Ok(NetworkName::Localnet),
+            _ => Err(format!(
+                "unknown network `{}`, expected mainnet, testnet, devnet or localnet",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Network {
+    /// Named network, used for the RPC url and the expected chain identifier.
+    pub name: NetworkName,
+    /// Explicit RPC url, overrides the url of the named network.
+    pub rpc_url: Option<String>,
+    /// Explicit websocket url.
+    pub ws_url: Option<String>,
+    /// RPC request timeout, in seconds.
+    pub request_timeout_secs: u64,
+    /// Expected chain identifier, overrides the one of the named network.
+    pub chain_id: Option<String>,
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Network {
+            name: NetworkName::Testnet,
+            rpc_url: None,
+            ws_url: None,
+            request_timeout_secs: 60,
+            chain_id: None,
+        }
+    }
+}
+
+impl Network {
+    pub fn rpc_url(&self) -> &str {
+        match (&self.rpc_url, self.name) {
+            (Some(rpc_url), _) => rpc_url.as_str(),
+            (None, NetworkName::Mainnet) => SUI_MAINNET_URL,
+            (None, NetworkName::Testnet) => SUI_TESTNET_URL,
+            (None, NetworkName::Devnet) => SUI_DEVNET_URL,
+            (None, NetworkName::Localnet) => SUI_LOCAL_NETWORK_URL,
+        }
+    }
+
+    /// Returns the chain identifier the connected node must report, if known.
+    /// Devnet and localnet are regenerated on reset, so they have none unless
+    /// set explicitly.
+    pub fn expected_chain_id(&self) -> Option<&str> {
+        match (&self.chain_id, self.name) {
+            (Some(chain_id), _) => Some(chain_id.as_str()),
+            (None, NetworkName::Mainnet) => Some("35834a8a"),
+            (None, NetworkName::Testnet) => Some("4c78adac"),
+            (None, NetworkName::Devnet | NetworkName::Localnet) => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Gas {
+    /// Safety margin added on top of the dry run gas cost, in percent.
+    pub margin_percent: u64,
+    /// Lower bound of the gas budget, in MIST.
+    pub min_budget: u64,
+    /// Upper bound of the gas budget per operation, in MIST.
+    pub max_budget: MaxGasBudget,
+}
+
+impl Default for Gas {
+    fn default() -> Self {
+        Gas {
+            margin_percent: 20,
+            min_budget: 2_000_000,
+            max_budget: MaxGasBudget::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct MaxGasBudget {
+    pub next_entry: u64,
+    pub draw: u64,
+    pub distribute: u64,
+    pub start_new_round: u64,
+}
+
+impl Default for MaxGasBudget {
+    fn default() -> Self {
+        MaxGasBudget {
+            next_entry: 50_000_000,
+            draw: 200_000_000,
+            distribute: 500_000_000,
+            start_new_round: 100_000_000,
+        }
+    }
+}
+
+impl MaxGasBudget {
+    pub fn for_operation(&self, operation: Operation) -> u64 {
+        match operation {
+            Operation::NextEntry => self.next_entry,
+            Operation::Draw => self.draw,
+            Operation::Distribute => self.distribute,
+            Operation::StartNewRound => self.start_new_round,
+        }
+    }
+}
+
+/// Backoff policy per error class, see `protocols::anglerfish::retry`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Retry {
+    pub transient: Backoff,
+    pub shared_object_conflict: Backoff,
+    pub object_locked: Backoff,
+    pub move_abort: Backoff,
+    pub gas_shortage: Backoff,
+    pub other: Backoff,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Retry {
+            transient: Backoff::new(500, 30_000, 2, 5),
+            shared_object_conflict: Backoff::new(1_000, 15_000, 2, 3),
+            // an equivocated object stays locked until the end of the epoch
+            object_locked: Backoff::new(60_000, 600_000, 2, 0),
+            // aborts such as "phase not over yet" are only retried after the
+            // phase has been read again
+            move_abort: Backoff::new(30_000, 30_000, 1, 0),
+            gas_shortage: Backoff::new(60_000, 600_000, 2, 0),
+            other: Backoff::new(30_000, 30_000, 1, 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Backoff {
+    /// Delay after the first failure, in milliseconds.
+    pub initial_delay_ms: u64,
+    /// Upper bound of the delay, in milliseconds.
+    pub max_delay_ms: u64,
+    /// Factor applied to the delay after each consecutive failure.
+    pub multiplier: u32,
+    /// Retries of one-off commands before giving up.
+    pub max_retries: u32,
+    /// Random reduction of each delay, in percent of the delay.
+    #[serde(default = "default_jitter_percent")]
+    pub jitter_percent: u32,
+}
+
+fn default_jitter_percent() -> u32 {
+    20
+}
+
+impl Backoff {
+    pub fn new(
+        initial_delay_ms: u64,
+        max_delay_ms: u64,
+        multiplier: u32,
+        max_retries: u32,
+    ) -> Self {
+        Backoff {
+            initial_delay_ms,
+            max_delay_ms,
+            multiplier,
+            max_retries,
+            jitter_percent: default_jitter_percent(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Scheduler {
+    /// Maximum time between two on-chain phase checks, in seconds.
+    pub poll_interval_secs: u64,
+    /// How long before a phase deadline the phase is re-checked, in seconds.
+    pub lead_time_secs: u64,
+    /// Delay added after a phase deadline before submitting, in milliseconds.
+    pub grace_ms: u64,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Scheduler {
+            poll_interval_secs: 30,
+            lead_time_secs: 5,
+            grace_ms: 1_000,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Confirmation {
+    /// How long to wait for a submitted transaction to be checkpointed, in seconds.
+    pub timeout_secs: u64,
+    /// Delay between two checkpoint checks, in milliseconds.
+    pub poll_interval_ms: u64,
+}
+
+impl Default for Confirmation {
+    fn default() -> Self {
+        Confirmation {
+            timeout_secs: 30,
+            poll_interval_ms: 500,
+        }
+    }
+}
+
+// Raw layout of the configuration file, before the preset is applied.
+#[derive(Deserialize)]
+struct RawConfig {
+    preset: Option<Preset>,
+    package_id: Option<ObjectID>,
+    original_package_id: Option<ObjectID>,
+    objects: Option<Objects>,
+    pool: Option<Pool>,
+    iterator: Iterator,
+    #[serde(default)]
+    network: Network,
+    #[serde(default)]
+    signer: Signer,
+    #[serde(default)]
+    gas: Gas,
+    #[serde(default)]
+    retry: Retry,
+    #[serde(default)]
+    scheduler: Scheduler,
+    #[serde(default)]
+    confirmation: Confirmation,
+    #[serde(default)]
+    leader: Leader,
+    #[serde(default)]
+    journal: Journal,
+    #[serde(default)]
+    server: Server,
+    #[serde(default)]
+    alerts: Alerts,
+}
+
+impl TryFrom<RawConfig> for Config {
+    type Error = String;
+
+    fn try_from(raw: RawConfig) -> Result<Self, Self::Error> {
+        let (package_id, objects, pool) = match raw.preset {
+            Some(Preset::Testnet) => (
+                raw.package_id
+                    .or_else(|| Some(ids::anglerfish_package_obj_id())),
+                raw.objects.or_else(|| Some(ids::testnet_objects())),
+                raw.pool.or_else(|| {
+                    Some(Pool {
+                        coin_type: ids::anglerfish_pool_coin_type(),
+                    })
+                }),
+            ),
+            None => (raw.package_id, raw.objects, raw.pool),
+        };
+
+        if raw.leader.backend != LeaseBackend::None {
+            let signer_timeout_secs = match raw.signer.backend {
+                SignerBackend::Remote => REMOTE_SIGNER_TIMEOUT.as_secs(),
+                _ => 0,
+            };
+            let min_ttl_secs = raw.leader.renew_interval_secs
+                + signer_timeout_secs
+                + raw.confirmation.timeout_secs;
+            if raw.leader.ttl_secs <= min_ttl_secs {
+                return Err(format!(
+                    "`leader.ttl_secs` ({}) must be greater than `leader.renew_interval_secs` + the signer timeout + `confirmation.timeout_secs` ({})",
+                    raw.leader.ttl_secs, min_ttl_secs
+                ));
+            }
+        }
+
+        let pool = pool.ok_or("missing field `pool`")?;
+        TypeTag::from_str(&pool.coin_type)
+            .map_err(|e| format!("invalid `pool.coin_type` {}: {}", pool.coin_type, e))?;
+
+        let package_id = package_id.ok_or("missing field `package_id`")?;
+        Ok(Config {
+            package_id,
+            original_package_id: raw.original_package_id.unwrap_or(package_id),
+            objects: objects.ok_or("missing field `objects`")?,
+            pool,
+            iterator: raw.iterator,
+            network: raw.network,
+            signer: raw.signer,
+            gas: raw.gas,
+            retry: raw.retry,
+            scheduler: raw.scheduler,
+            confirmation: raw.confirmation,
+            leader: raw.leader,
+            journal: raw.journal,
+            server: raw.server,
+            alerts: raw.alerts,
+        })
+    }
 }
 
 // Function to load the configuration from a file.  This function now handles
@@ -37,9 +585,11 @@ pub struct Iterator {
 pub fn load_config<P: AsRef<Path>>(path: P) -> Result<Config> {
     let path = path.as_ref();
 
-    let config_string = fs::read_to_string(path)?;
+    let config_string = fs::read_to_string(path)
+        .map_err(|e| AnglerfishError::Config(format!("Failed to read {:?}: {}", path, e)))?;
 
-    let config: Config = toml::from_str(&config_string)?;
+    let config: Config = toml::from_str(&config_string)
+        .map_err(|e| AnglerfishError::Config(format!("Failed to parse {:?}: {}", path, e)))?;
 
     Ok(config)
 }
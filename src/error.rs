@@ -0,0 +1,322 @@
+use sui_sdk::types::{base_types::ObjectID, digests::TransactionDigest};
+use thiserror::Error;
+
+use crate::{
+    helper::gas::GasError,
+    protocols::anglerfish::{engine::Operation, types::phase_info::Phase},
+};
+
+/// Rejections of `execute_transaction_block` for owned objects locked by
+/// another transaction. The validators only report them in the error message.
+const OBJECT_LOCKED_ERRORS: &[&str] = &[
+    "ObjectLockConflict",
+    "ObjectLockedAtEpoch",
+    "already locked by a different transaction",
+    "equivocat",
+];
+
+/// Rejections of `execute_transaction_block` for shared object versions.
+const SHARED_OBJECT_ERRORS: &[&str] = &[
+    "ObjectVersionUnavailableForConsumption",
+    "is not available for consumption",
+];
+
+/// Answers of `get_transaction_block` for a digest the node does not know.
+const TRANSACTION_NOT_FOUND_ERRORS: &[&str] = &[
+    "Could not find the referenced transaction",
+    "TransactionNotFound",
+];
+
+/// Execution statuses of transactions cancelled by shared object congestion.
+const SHARED_OBJECT_STATUSES: &[&str] = &["ExecutionCancelledDueToSharedObjectCongestion"];
+
+pub type Result<T, E = AnglerfishError> = std::result::Result<T, E>;
+
+/// Errors of the Anglerfish iterator library.
+#[derive(Debug, Error)]
+pub enum AnglerfishError {
+    /// The configuration cannot be read, or does not match the chain.
+    #[error("Config error: {0}")]
+    Config(String),
+
+    /// An RPC request failed, or the node rejected the transaction.
+    #[error("RPC error: {0}")]
+    Rpc(#[from] sui_sdk::error::Error),
+
+    #[error("Could not find object with ID: {0}")]
+    ObjectNotFound(ObjectID),
+
+    /// The object exists but its content cannot be read as the expected type.
+    #[error("Failed to decode object: {0}")]
+    ObjectDecode(String),
+
+    /// An event of the Anglerfish package cannot be read as the expected type.
+    #[error("Failed to decode event: {0}")]
+    EventDecode(String),
+
+    #[error("Failed to build transaction: {0}")]
+    TransactionBuild(String),
+
+    /// The operation cannot advance the phase the protocol is in.
+    #[error("{operation} does not apply to the {phase:?} phase")]
+    OperationNotApplicable { operation: Operation, phase: Phase },
+
+    /// The transaction aborted in Move, during the dry run (`digest` is
+    /// `None`) or on-chain.
+    #[error(
+        "Transaction is reverted: move abort in {module}::{} with code {code}",
+        .function.as_deref().unwrap_or("?")
+    )]
+    MoveAbort {
+        module: String,
+        function: Option<String>,
+        code: u64,
+        digest: Option<TransactionDigest>,
+    },
+
+    /// The dry run failed for another reason than a Move abort.
+    #[error("Transaction is reverted: {0}")]
+    Reverted(String),
+
+    /// An owned object of the transaction is locked by another transaction,
+    /// possibly until the end of the epoch.
+    #[error("Object locked: {0}")]
+    ObjectLocked(String),
+
+    /// A shared object version was not available, or the transaction was
+    /// cancelled by shared object congestion.
+    #[error("Shared object conflict: {0}")]
+    SharedObjectConflict(String),
+
+    /// The transaction was executed but its effects are not `Success`.
+    #[error("Transaction {digest} failed: {error}")]
+    TransactionFailed {
+        digest: TransactionDigest,
+        error: String,
+    },
+
+    /// The transaction was submitted but its outcome is not known, it may
+    /// still be executed. It must be resolved from the journal, not resent.
+    #[error("Outcome of transaction {digest} is unknown: {error}")]
+    OutcomeUnknown {
+        digest: TransactionDigest,
+        error: String,
+    },
+
+    #[error(transparent)]
+    Gas(#[from] GasError),
+
+    #[error("Signer error: {0}")]
+    Signer(String),
+
+    /// The signing service cannot be reached or is overloaded, the same
+    /// request may succeed later.
+    #[error("Signer unavailable: {0}")]
+    SignerUnavailable(String),
+
+    /// The leader lease cannot be read or written.
+    #[error("Lease error: {0}")]
+    Lease(String),
+
+    /// The transaction journal cannot be read or written.
+    #[error("Journal error: {0}")]
+    Journal(String),
+
+    /// An alert could not be delivered.
+    #[error("Notifier error: {0}")]
+    Notifier(String),
+}
+
+impl AnglerfishError {
+    /// Builds the error of a failed execution status, decoding Move aborts.
+    /// `digest` is `None` for dry runs.
+    pub fn from_execution_error(error: &str, digest: Option<TransactionDigest>) -> Self {
+        if let Some((module, function, code)) = parse_move_abort(error) {
+            return AnglerfishError::MoveAbort {
+                module,
+                function,
+                code,
+                digest,
+            };
+        }
+        if SHARED_OBJECT_STATUSES
+            .iter()
+            .any(|status| error.starts_with(status))
+        {
+            return AnglerfishError::SharedObjectConflict(error.to_string());
+        }
+        match digest {
+            Some(digest) => AnglerfishError::TransactionFailed {
+                digest,
+                error: error.to_string(),
+            },
+            None => AnglerfishError::Reverted(error.to_string()),
+        }
+    }
+
+    /// Builds the error of a transaction response without the requested
+    /// effects. The transaction may have been executed, so its outcome is
+    /// unknown rather than failed.
+    pub fn missing_effects(digest: TransactionDigest) -> Self {
+        AnglerfishError::OutcomeUnknown {
+            digest,
+            error: "no effects returned".to_string(),
+        }
+    }
+
+    /// Builds the error of a failed `execute_transaction_block` request for
+    /// the transaction `digest`. Lock and version conflicts are rejections of
+    /// the transaction, any other failure leaves its outcome unknown.
+    pub fn from_submission_error(error: sui_sdk::error::Error, digest: TransactionDigest) -> Self {
+        // only the validators reject a transaction, through an RPC error
+        let sui_sdk::error::Error::RpcError(e) = &error else {
+            return AnglerfishError::OutcomeUnknown {
+                digest,
+                error: error.to_string(),
+            };
+        };
+        let message = e.to_string();
+        if OBJECT_LOCKED_ERRORS.iter().any(|p| message.contains(p)) {
+            AnglerfishError::ObjectLocked(message)
+        } else if SHARED_OBJECT_ERRORS.iter().any(|p| message.contains(p)) {
+            AnglerfishError::SharedObjectConflict(message)
+        } else {
+            AnglerfishError::OutcomeUnknown {
+                digest,
+                error: message,
+            }
+        }
+    }
+}
+
+/// Returns true if `error` is the node answering that it does not know the
+/// transaction, as opposed to the request failing.
+pub fn is_transaction_not_found(error: &sui_sdk::error::Error) -> bool {
+    match error {
+        sui_sdk::error::Error::RpcError(e) => {
+            let message = e.to_string();
+            TRANSACTION_NOT_FOUND_ERRORS
+                .iter()
+                .any(|p| message.contains(p))
+        }
+        _ => false,
+    }
+}
+
+/// Decodes a Move abort from its debug representation, e.g.
+/// `MoveAbort(MoveLocation { module: ModuleId { address: .., name: Identifier("phase") },
+/// function: 2, instruction: 14, function_name: Some("next_entry") }, 3)`.
+fn parse_move_abort(message: &str) -> Option<(String, Option<String>, u64)> {
+    let abort = &message[message.find("MoveAbort(")?..];
+
+    let module = quoted_after(abort, "name: Identifier(\"")?;
+    let function = quoted_after(abort, "function_name: Some(\"");
+
+    // the abort code follows the closing brace of the `MoveLocation`
+    let location_end = abort.find("function_name")?;
+    let code_start = location_end + abort[location_end..].find("}, ")? + 3;
+    let code = abort[code_start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .ok()?;
+
+    Some((module.to_string(), function.map(str::to_string), code))
+}
+
+fn quoted_after<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    let start = s.find(prefix)? + prefix.len();
+    let len = s[start..].find('"')?;
+    Some(&s[start..start + len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABORT_WITH_FUNCTION: &str = "MoveAbort(MoveLocation { module: ModuleId { address: 09c23dc75590103509b266b5f54fa38e73313b5ec9ddba480951efd9c70bec00, name: Identifier(\"phase\") }, function: 2, instruction: 14, function_name: Some(\"next_entry\") }, 3) in command 0";
+    const ABORT_WITHOUT_FUNCTION: &str = "MoveAbort(MoveLocation { module: ModuleId { address: 09c23dc75590103509b266b5f54fa38e73313b5ec9ddba480951efd9c70bec00, name: Identifier(\"prize_pool\") }, function: 5, instruction: 31, function_name: None }, 13906834260000000001) in command 1";
+
+    #[test]
+    fn parse_move_abort_with_function_name() {
+        assert_eq!(
+            parse_move_abort(ABORT_WITH_FUNCTION),
+            Some(("phase".to_string(), Some("next_entry".to_string()), 3))
+        );
+    }
+
+    #[test]
+    fn parse_move_abort_without_function_name() {
+        assert_eq!(
+            parse_move_abort(ABORT_WITHOUT_FUNCTION),
+            Some(("prize_pool".to_string(), None, 13906834260000000001))
+        );
+    }
+
+    #[test]
+    fn parse_move_abort_ignores_other_errors() {
+        assert_eq!(parse_move_abort("InsufficientGas in command 0"), None);
+        assert_eq!(parse_move_abort("MoveAbort(truncated"), None);
+    }
+
+    #[test]
+    fn from_execution_error_variants() {
+        let digest = TransactionDigest::random();
+
+        match AnglerfishError::from_execution_error(ABORT_WITH_FUNCTION, Some(digest)) {
+            AnglerfishError::MoveAbort {
+                module,
+                function,
+                code,
+                digest: Some(abort_digest),
+            } => {
+                assert_eq!(module, "phase");
+                assert_eq!(function.as_deref(), Some("next_entry"));
+                assert_eq!(code, 3);
+                assert_eq!(abort_digest, digest);
+            }
+            error => panic!("expected a move abort, got {:?}", error),
+        }
+        assert!(matches!(
+            AnglerfishError::from_execution_error(
+                "ExecutionCancelledDueToSharedObjectCongestion { congested_objects: [] }",
+                Some(digest)
+            ),
+            AnglerfishError::SharedObjectConflict(_)
+        ));
+        assert!(matches!(
+            AnglerfishError::from_execution_error("InsufficientGas", Some(digest)),
+            AnglerfishError::TransactionFailed { .. }
+        ));
+        assert!(matches!(
+            AnglerfishError::from_execution_error("InsufficientGas", None),
+            AnglerfishError::Reverted(_)
+        ));
+    }
+
+    #[test]
+    fn submission_error_without_rejection_leaves_the_outcome_unknown() {
+        let digest = TransactionDigest::random();
+        let error = sui_sdk::error::Error::DataError("connection reset".to_string());
+        match AnglerfishError::from_submission_error(error, digest) {
+            AnglerfishError::OutcomeUnknown {
+                digest: unknown_digest,
+                ..
+            } => assert_eq!(unknown_digest, digest),
+            error => panic!("expected an unknown outcome, got {:?}", error),
+        }
+        assert!(matches!(
+            AnglerfishError::missing_effects(digest),
+            AnglerfishError::OutcomeUnknown { .. }
+        ));
+    }
+
+    #[test]
+    fn failed_request_is_not_a_missing_transaction() {
+        let error = sui_sdk::error::Error::DataError(
+            "Could not find the referenced transaction".to_string(),
+        );
+        assert!(!is_transaction_not_found(&error));
+    }
+}
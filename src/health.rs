@@ -0,0 +1,168 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use serde_json::Value;
+use sui_sdk::types::base_types::SuiAddress;
+
+use crate::helper::duration::current_timestamp_ms;
+
+/// Progress of the iterator loop, reported by the health endpoints.
+///
+/// The loop updates it as it goes, the endpoints only read it.
+pub struct Health {
+    liveness_timeout: Duration,
+    state: Mutex<HealthState>,
+}
+
+struct HealthState {
+    // The loop is alive until then, the liveness timeout after its last tick
+    // or after the end of its current sleep.
+    alive_until: Instant,
+    last_tick_at_ms: u64,
+    signer_address: Option<SuiAddress>,
+    config_validated: bool,
+    phase_info: Option<Value>,
+    last_action: Option<Event>,
+    last_error: Option<Event>,
+}
+
+/// Something which happened at a given time.
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub message: String,
+    pub at_ms: u64,
+}
+
+impl Event {
+    fn now(message: String) -> Self {
+        Event {
+            message,
+            at_ms: current_timestamp_ms(),
+        }
+    }
+}
+
+/// Body of the `/status` endpoint.
+#[derive(Debug, Serialize)]
+pub struct Status {
+    pub alive: bool,
+    pub last_tick_at_ms: u64,
+    pub signer_address: Option<SuiAddress>,
+    pub config_validated: bool,
+    pub phase_info: Option<Value>,
+    pub last_action: Option<Event>,
+    pub last_error: Option<Event>,
+}
+
+impl Health {
+    /// The loop is considered alive until it has not ticked for
+    /// `liveness_timeout`, not counting the time it sleeps.
+    pub fn new(liveness_timeout: Duration) -> Self {
+        Health {
+            liveness_timeout,
+            state: Mutex::new(HealthState {
+                alive_until: Instant::now() + liveness_timeout,
+                last_tick_at_ms: current_timestamp_ms(),
+                signer_address: None,
+                config_validated: false,
+                phase_info: None,
+                last_action: None,
+                last_error: None,
+            }),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, HealthState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records an iteration of the loop, whatever its outcome.
+    pub fn tick(&self) {
+        let mut state = self.state();
+        state.alive_until = Instant::now() + self.liveness_timeout;
+        state.last_tick_at_ms = current_timestamp_ms();
+    }
+
+    /// Records that the loop is about to sleep for `duration`, e.g. backing
+    /// off after a failure, so a sleep longer than the liveness timeout is not
+    /// reported as a stuck loop.
+    pub fn sleeping(&self, duration: Duration) {
+        self.state().alive_until = Instant::now() + duration + self.liveness_timeout;
+    }
+
+    pub fn signer_loaded(&self, address: SuiAddress) {
+        self.state().signer_address = Some(address);
+    }
+
+    pub fn config_validated(&self) {
+        self.state().config_validated = true;
+    }
+
+    /// Records the last phase read, as JSON.
+    pub fn phase_info(&self, phase_info: &impl Serialize) {
+        self.state().phase_info = serde_json::to_value(phase_info).ok();
+    }
+
+    pub fn action(&self, action: impl ToString) {
+        self.state().last_action = Some(Event::now(action.to_string()));
+    }
+
+    pub fn error(&self, error: impl ToString) {
+        self.state().last_error = Some(Event::now(error.to_string()));
+    }
+
+    pub fn is_alive(&self) -> bool {
+        Instant::now() <= self.state().alive_until
+    }
+
+    /// Address of the signer, once loaded.
+    pub fn signer_address(&self) -> Option<SuiAddress> {
+        self.state().signer_address
+    }
+
+    pub fn is_config_validated(&self) -> bool {
+        self.state().config_validated
+    }
+
+    pub fn status(&self) -> Status {
+        let alive = self.is_alive();
+        let state = self.state();
+        Status {
+            alive,
+            last_tick_at_ms: state.last_tick_at_ms,
+            signer_address: state.signer_address,
+            config_validated: state.config_validated,
+            phase_info: state.phase_info.clone(),
+            last_action: state.last_action.clone(),
+            last_error: state.last_error.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::sleep;
+
+    use super::*;
+
+    #[test]
+    fn alive_while_ticking_or_sleeping() {
+        let health = Health::new(Duration::from_millis(50));
+        assert!(health.is_alive());
+        sleep(Duration::from_millis(80));
+        assert!(!health.is_alive());
+
+        health.tick();
+        assert!(health.is_alive());
+
+        // a backoff longer than the liveness timeout
+        health.sleeping(Duration::from_millis(500));
+        sleep(Duration::from_millis(200));
+        assert!(health.is_alive());
+        sleep(Duration::from_millis(400));
+        assert!(!health.is_alive());
+    }
+}
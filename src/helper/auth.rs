@@ -0,0 +1,20 @@
+use std::env;
+
+use reqwest::RequestBuilder;
+
+/// Reads the bearer token of an HTTP service from the environment variable
+/// `var`, if one is configured.
+pub fn bearer_token_from_env(var: Option<&str>) -> Result<Option<String>, String> {
+    var.map(|var| {
+        env::var(var).map_err(|e| format!("Failed to read the token from ${}: {}", var, e))
+    })
+    .transpose()
+}
+
+/// Sends `token`, if any, as the bearer token of `request`.
+pub fn with_bearer_auth(request: RequestBuilder, token: Option<&str>) -> RequestBuilder {
+    match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
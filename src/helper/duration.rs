@@ -1,3 +1,23 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 pub fn duration_ms_to_minutes(duration_ms: u64) -> u64 {
     duration_ms / 1000 / 60
 }
+
+/// Returns the current unix timestamp in milliseconds.
+pub fn current_timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Formats a unix timestamp in milliseconds as an RFC 3339 date.
+pub fn format_timestamp_ms(timestamp_ms: u64) -> String {
+    humantime::format_rfc3339_seconds(UNIX_EPOCH + Duration::from_millis(timestamp_ms)).to_string()
+}
+
+/// Formats a duration in milliseconds with second precision, e.g. `1h 2m 3s`.
+pub fn format_duration_ms(duration_ms: u64) -> String {
+    humantime::format_duration(Duration::from_secs(duration_ms / 1000)).to_string()
+}
@@ -0,0 +1,243 @@
+use sui_sdk::{
+    SUI_COIN_TYPE, SuiClient,
+    error::Error as SuiRpcError,
+    rpc_types::Coin,
+    types::base_types::{ObjectRef, SuiAddress},
+};
+use thiserror::Error;
+
+/// Maximum number of gas coins a transaction can pay with
+/// (`max_gas_payment_objects` of the protocol config).
+pub const MAX_GAS_PAYMENT_OBJECTS: usize = 256;
+
+#[derive(Debug, Error)]
+pub enum GasError {
+    #[error(
+        "Insufficient gas balance for {address}: {required} MIST required, {available} MIST available (short by {shortfall} MIST)"
+    )]
+    InsufficientBalance {
+        address: SuiAddress,
+        required: u64,
+        available: u64,
+        shortfall: u64,
+    },
+    #[error(
+        "Gas balance of {address} is spread over {coin_count} coins, the {MAX_GAS_PAYMENT_OBJECTS} largest cannot cover {required} MIST. Merge the SUI coins first."
+    )]
+    TooFragmented {
+        address: SuiAddress,
+        required: u64,
+        coin_count: usize,
+    },
+    #[error(
+        "Estimated gas of {operation} ({estimated} MIST) exceeds its maximum budget of {max_budget} MIST"
+    )]
+    BudgetExceeded {
+        operation: String,
+        estimated: u64,
+        max_budget: u64,
+    },
+    #[error("Failed to fetch gas coins: {0}")]
+    Rpc(#[from] SuiRpcError),
+}
+
+/// Gas coins selected to pay for a transaction.
+#[derive(Debug, Clone)]
+pub struct GasSelection {
+    pub coins: Vec<ObjectRef>,
+    /// Total SUI balance of the address when the coins were selected.
+    pub balance: u64,
+}
+
+// Gas coin selection helper trait for SuiClient
+
+#[async_trait::async_trait]
+pub trait GasCoinSelector {
+    /// Returns SUI coins of `address` covering `budget`, largest first, along
+    /// with the total balance of the address.
+    /// When more than one coin is returned, they are merged into the first
+    /// one as part of the gas payment.
+    async fn select_gas_coins(
+        &self,
+        address: SuiAddress,
+        budget: u64,
+    ) -> Result<GasSelection, GasError>;
+
+    async fn get_all_sui_coins(&self, address: SuiAddress) -> Result<Vec<Coin>, GasError>;
+}
+
+#[async_trait::async_trait]
+impl GasCoinSelector for SuiClient {
+    async fn select_gas_coins(
+        &self,
+        address: SuiAddress,
+        budget: u64,
+    ) -> Result<GasSelection, GasError> {
+        let coins = self.get_all_sui_coins(address).await?;
+        select_coins(address, &coins, budget)
+    }
+
+    async fn get_all_sui_coins(&self, address: SuiAddress) -> Result<Vec<Coin>, GasError> {
+        let mut coins = vec![];
+        let mut cursor = None;
+        loop {
+            let page = self
+                .coin_read_api()
+                .get_coins(address, Some(SUI_COIN_TYPE.to_string()), cursor, None)
+                .await?;
+            coins.extend(page.data);
+            if !page.has_next_page {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        Ok(coins)
+    }
+}
+
+/// Picks the largest of `coins` until they cover `budget`, using at most
+/// `MAX_GAS_PAYMENT_OBJECTS` coins.
+pub fn select_coins(
+    address: SuiAddress,
+    coins: &[Coin],
+    budget: u64,
+) -> Result<GasSelection, GasError> {
+    let mut coins: Vec<&Coin> = coins.iter().collect();
+    coins.sort_by(|a, b| b.balance.cmp(&a.balance));
+    let available: u64 = coins
+        .iter()
+        .fold(0, |total, coin| total.saturating_add(coin.balance));
+
+    let mut selected = vec![];
+    let mut selected_balance: u64 = 0;
+    for coin in coins.iter().take(MAX_GAS_PAYMENT_OBJECTS) {
+        selected.push(coin.object_ref());
+        selected_balance = selected_balance.saturating_add(coin.balance);
+        if selected_balance >= budget {
+            return Ok(GasSelection {
+                coins: selected,
+                balance: available,
+            });
+        }
+    }
+
+    if available >= budget {
+        return Err(GasError::TooFragmented {
+            address,
+            required: budget,
+            coin_count: coins.len(),
+        });
+    }
+
+    Err(GasError::InsufficientBalance {
+        address,
+        required: budget,
+        available,
+        shortfall: budget - available,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_sdk::types::{
+        base_types::{ObjectID, SequenceNumber},
+        digests::{ObjectDigest, TransactionDigest},
+    };
+
+    use super::*;
+
+    fn coin(balance: u64) -> Coin {
+        Coin {
+            coin_type: SUI_COIN_TYPE.to_string(),
+            coin_object_id: ObjectID::random(),
+            version: SequenceNumber::new(),
+            digest: ObjectDigest::random(),
+            balance,
+            previous_transaction: TransactionDigest::random(),
+        }
+    }
+
+    /// Balances of the selected coins, in selection order.
+    fn select(balances: &[u64], budget: u64) -> Result<Vec<u64>, GasError> {
+        let coins: Vec<Coin> = balances.iter().map(|balance| coin(*balance)).collect();
+        let selection = select_coins(SuiAddress::ZERO, &coins, budget)?;
+        assert_eq!(selection.balance, balances.iter().sum::<u64>());
+        Ok(selection
+            .coins
+            .iter()
+            .map(|(id, ..)| {
+                coins
+                    .iter()
+                    .find(|coin| coin.coin_object_id == *id)
+                    .unwrap()
+                    .balance
+            })
+            .collect())
+    }
+
+    #[test]
+    fn select_coins_largest_first() {
+        let cases: [(&[u64], u64, &[u64]); 5] = [
+            // (coin balances, budget, selected balances)
+            (&[100], 100, &[100]),
+            (&[10, 500, 50], 100, &[500]),
+            (&[10, 60, 50], 100, &[60, 50]),
+            (&[10, 60, 50], 120, &[60, 50, 10]),
+            (&[30, 30, 30, 30], 90, &[30, 30, 30]),
+        ];
+        for (balances, budget, expected) in cases {
+            assert_eq!(
+                select(balances, budget).unwrap(),
+                expected,
+                "{:?} for {}",
+                balances,
+                budget
+            );
+        }
+    }
+
+    #[test]
+    fn select_coins_up_to_the_payment_cap() {
+        let balances = [1; MAX_GAS_PAYMENT_OBJECTS + 44];
+        let selected = select(&balances, MAX_GAS_PAYMENT_OBJECTS as u64).unwrap();
+        assert_eq!(selected.len(), MAX_GAS_PAYMENT_OBJECTS);
+
+        // the balance is enough, but not in the largest coins
+        match select(&balances, MAX_GAS_PAYMENT_OBJECTS as u64 + 1) {
+            Err(GasError::TooFragmented {
+                required,
+                coin_count,
+                ..
+            }) => {
+                assert_eq!(required, MAX_GAS_PAYMENT_OBJECTS as u64 + 1);
+                assert_eq!(coin_count, balances.len());
+            }
+            result => panic!("expected too fragmented, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn select_coins_reports_the_shortfall() {
+        let cases: [(&[u64], u64, u64); 3] = [
+            // (coin balances, budget, shortfall)
+            (&[], 100, 100),
+            (&[40, 50], 100, 10),
+            (&[1; MAX_GAS_PAYMENT_OBJECTS + 1], 300, 300 - 257),
+        ];
+        for (balances, budget, expected) in cases {
+            match select(balances, budget) {
+                Err(GasError::InsufficientBalance {
+                    required,
+                    available,
+                    shortfall,
+                    ..
+                }) => {
+                    assert_eq!(required, budget);
+                    assert_eq!(available, budget - expected);
+                    assert_eq!(shortfall, expected);
+                }
+                result => panic!("expected insufficient balance, got {:?}", result),
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
+pub mod auth;
 pub mod duration;
+pub mod gas;
+pub mod network;
 pub mod sui;
 pub mod type_input;
-pub mod wallet;
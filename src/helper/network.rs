@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use sui_sdk::{SuiClient, SuiClientBuilder};
+use tracing::info;
+
+use crate::{
+    config,
+    error::{AnglerfishError, Result},
+};
+
+/// Connects to the configured network and checks that the node serves the
+/// expected chain, so a mainnet config never runs against another network.
+pub async fn build_sui_client(network: &config::Network) -> Result<SuiClient> {
+    let mut builder = SuiClientBuilder::default()
+        .request_timeout(Duration::from_secs(network.request_timeout_secs));
+    if let Some(ws_url) = &network.ws_url {
+        builder = builder.ws_url(ws_url);
+    }
+
+    let rpc_url = network.rpc_url();
+    info!(%rpc_url, "Connecting to the RPC");
+    let sui_client = builder.build(rpc_url).await?;
+
+    let chain_id = sui_client.read_api().get_chain_identifier().await?;
+    match network.expected_chain_id() {
+        Some(expected) if expected != chain_id => {
+            return Err(AnglerfishError::Config(format!(
+                "Chain identifier mismatch: expected {} ({:?}) but {} serves {}",
+                expected, network.name, rpc_url, chain_id
+            )));
+        }
+        Some(_) => info!(%chain_id, "Chain identifier checked"),
+        None => info!(%chain_id, "Chain identifier not checked"),
+    }
+
+    Ok(sui_client)
+}
@@ -1,6 +1,3 @@
-use std::str::FromStr;
-
-use anyhow::{Result, anyhow};
 use serde::de::DeserializeOwned;
 use sui_sdk::{
     SuiClient,
@@ -12,11 +9,13 @@ use sui_sdk::{
     },
 };
 
+use crate::error::{AnglerfishError, Result};
+
 // Object build helper trait for SuiClient
 
 #[async_trait::async_trait]
 pub trait SuiObjectBuilder {
-    async fn fetch_obj<T>(&self, obj_id: &str) -> Result<T>
+    async fn fetch_obj<T>(&self, obj_id: ObjectID) -> Result<T>
     where
         T: DeserializeOwned;
 
@@ -24,28 +23,25 @@ pub trait SuiObjectBuilder {
     where
         T: DeserializeOwned;
 
-    async fn object_ref(&self, object_id: &str) -> Result<ObjectRef>;
-    async fn owned_obj(&self, object_id: &str) -> Result<ObjectArg>;
-    async fn shared_obj_mut(&self, object_id: &str) -> Result<ObjectArg>;
-    async fn shared_obj(&self, object_id: &str) -> Result<ObjectArg>;
+    async fn object_ref(&self, object_id: ObjectID) -> Result<ObjectRef>;
+    async fn owned_obj(&self, object_id: ObjectID) -> Result<ObjectArg>;
+    async fn shared_obj_mut(&self, object_id: ObjectID) -> Result<ObjectArg>;
+    async fn shared_obj(&self, object_id: ObjectID) -> Result<ObjectArg>;
     async fn clock(&self) -> Result<ObjectArg>;
     async fn randomness(&self) -> Result<ObjectArg>;
 }
 
 #[async_trait::async_trait]
 impl SuiObjectBuilder for SuiClient {
-    async fn fetch_obj<T>(&self, obj_id: &str) -> Result<T>
+    async fn fetch_obj<T>(&self, obj_id: ObjectID) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        let obj_id = ObjectID::from_str(obj_id)?;
         let resp = self
             .read_api()
             .get_object_with_options(obj_id, SuiObjectDataOptions::default().with_bcs())
             .await?;
-        let data = resp
-            .data
-            .ok_or_else(|| anyhow!("Could not find object with ID: {}", obj_id))?;
+        let data = resp.data.ok_or(AnglerfishError::ObjectNotFound(obj_id))?;
         self.parse_obj_bcs(data).await
     }
 
@@ -53,49 +49,53 @@ impl SuiObjectBuilder for SuiClient {
     where
         T: DeserializeOwned,
     {
-        let bcs_data = data
-            .bcs
-            .ok_or_else(|| anyhow!("Fetched object but no data was returned"))?;
-        let move_obj = bcs_data
-            .try_as_move()
-            .ok_or_else(|| anyhow!("Fetched object is not a Move Object"))?;
-        let deserialized_data: T = move_obj.deserialize()?;
+        let bcs_data = data.bcs.ok_or_else(|| {
+            AnglerfishError::ObjectDecode(format!(
+                "Fetched object {} but no data was returned",
+                data.object_id
+            ))
+        })?;
+        let move_obj = bcs_data.try_as_move().ok_or_else(|| {
+            AnglerfishError::ObjectDecode(format!(
+                "Fetched object {} is not a Move Object",
+                data.object_id
+            ))
+        })?;
+        let deserialized_data: T = move_obj.deserialize().map_err(|e| {
+            AnglerfishError::ObjectDecode(format!("Object {}: {}", data.object_id, e))
+        })?;
         Ok(deserialized_data)
     }
 
-    async fn object_ref(&self, object_id: &str) -> Result<ObjectRef> {
-        let object_id = ObjectID::from_str(object_id)?;
+    async fn object_ref(&self, object_id: ObjectID) -> Result<ObjectRef> {
         let object: SuiObjectResponse = self
             .read_api()
             .get_object_with_options(object_id, SuiObjectDataOptions::default())
             .await?;
 
-        if let Some(error) = object.error {
-            return Err(anyhow!(error));
+        if object.error.is_some() {
+            return Err(AnglerfishError::ObjectNotFound(object_id));
         }
 
-        let data = object.data.ok_or_else(|| {
-            anyhow!(
-                "Fetched object but no data was returned for object {:?}",
-                object_id
-            )
-        })?;
+        let data = object
+            .data
+            .ok_or(AnglerfishError::ObjectNotFound(object_id))?;
 
         Ok(data.object_ref())
     }
 
-    async fn owned_obj(&self, object_id: &str) -> Result<ObjectArg> {
+    async fn owned_obj(&self, object_id: ObjectID) -> Result<ObjectArg> {
         Ok(ObjectArg::ImmOrOwnedObject(
             self.object_ref(object_id).await?,
         ))
     }
 
-    async fn shared_obj_mut(&self, object_id: &str) -> Result<ObjectArg> {
-        inner_shared_obj_mutably_arg(self, ObjectID::from_str(object_id)?, true).await
+    async fn shared_obj_mut(&self, object_id: ObjectID) -> Result<ObjectArg> {
+        inner_shared_obj_mutably_arg(self, object_id, true).await
     }
 
-    async fn shared_obj(&self, object_id: &str) -> Result<ObjectArg> {
-        inner_shared_obj_mutably_arg(self, ObjectID::from_str(object_id)?, false).await
+    async fn shared_obj(&self, object_id: ObjectID) -> Result<ObjectArg> {
+        inner_shared_obj_mutably_arg(self, object_id, false).await
     }
 
     /// Returns the clock object as a shared object argument.
@@ -128,11 +128,15 @@ async fn inner_shared_obj_mutably_arg(
         .await?;
     let initial_shared_version = obj
         .data
-        .ok_or_else(|| anyhow!("Could not find object with ID: {}", obj_id))?
+        .ok_or(AnglerfishError::ObjectNotFound(obj_id))?
         .owner
-        .ok_or_else(|| anyhow!("Object has no owner fields"))?
+        .ok_or_else(|| {
+            AnglerfishError::ObjectDecode(format!("Object {} has no owner fields", obj_id))
+        })?
         .start_version()
-        .ok_or_else(|| anyhow!("Shared object has no start version"))?;
+        .ok_or_else(|| {
+            AnglerfishError::ObjectDecode(format!("Object {} is not a shared object", obj_id))
+        })?;
 
     Ok(ObjectArg::SharedObject {
         id: obj_id,
@@ -0,0 +1,350 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+use sui_sdk::{
+    rpc_types::{SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions},
+    types::{
+        digests::TransactionDigest, messages_checkpoint::CheckpointSequenceNumber,
+        transaction::ProgrammableTransaction,
+    },
+};
+use tracing::{info, warn};
+
+use crate::{
+    error::{AnglerfishError, Result},
+    helper::duration::current_timestamp_ms,
+    protocols::anglerfish::{
+        client::AnglerfishClient, confirmation::AnglerfishConfirmation, engine::Operation,
+        sui_client::gas_used, types::phase_info::Phase,
+    },
+};
+
+/// One line of the journal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub timestamp_ms: u64,
+    pub operation: Operation,
+    /// Round number and phase read before the operation was built.
+    pub round_number: u64,
+    pub phase: Phase,
+    #[serde(flatten)]
+    pub record: JournalRecord,
+}
+
+/// What happened to an attempted operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalRecord {
+    /// The transaction was dry run, `error` is set if the dry run failed.
+    DryRun {
+        ptb: String,
+        estimated_gas: u64,
+        error: Option<String>,
+    },
+    /// The transaction is about to be submitted. Until a later record of the
+    /// same digest, its outcome is unknown.
+    Submitted {
+        digest: TransactionDigest,
+        gas_budget: u64,
+    },
+    /// The effects of the transaction are known.
+    Executed {
+        digest: TransactionDigest,
+        status: String,
+        gas_used: u64,
+    },
+    /// The phase was re-read after the transaction was checkpointed.
+    Confirmed {
+        digest: TransactionDigest,
+        checkpoint: Option<CheckpointSequenceNumber>,
+        transition_observed: bool,
+    },
+    /// The transaction was rejected by the validators, or could not be found
+    /// on-chain after a restart.
+    Abandoned {
+        digest: TransactionDigest,
+        reason: String,
+    },
+}
+
+/// Append-only JSONL journal of the iterator transactions.
+///
+/// Every attempt is recorded before and after submission, so a restarted
+/// iterator knows which digests were submitted without a known outcome and can
+/// resolve them before sending the same transition again.
+pub struct Journal {
+    path: PathBuf,
+    file: Mutex<File>,
+    // Submitted entries without a later `Executed` or `Abandoned` record.
+    pending: Mutex<HashMap<TransactionDigest, JournalEntry>>,
+}
+
+fn journal_error(path: &Path, e: impl std::fmt::Display) -> AnglerfishError {
+    AnglerfishError::Journal(format!("{:?}: {}", path, e))
+}
+
+impl Journal {
+    /// Opens the journal at `path`, creating it if needed, and replays it to
+    /// find the pending transactions.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .read(true)
+            .open(&path)
+            .map_err(|e| journal_error(&path, e))?;
+
+        let mut pending = HashMap::new();
+        for (index, line) in BufReader::new(&file).lines().enumerate() {
+            let line = line.map_err(|e| journal_error(&path, e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // a crash while appending can leave a truncated last line
+            let entry: JournalEntry = match serde_json::from_str(&line) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    warn!(line = index + 1, error = %e, "Skipping invalid journal line");
+                    continue;
+                }
+            };
+            track_pending(&mut pending, entry);
+        }
+        // terminate a truncated last line, so the next record is not lost with it
+        if ends_without_newline(&file).map_err(|e| journal_error(&path, e))? {
+            (&file)
+                .write_all(b"\n")
+                .map_err(|e| journal_error(&path, e))?;
+        }
+
+        Ok(Journal {
+            path,
+            file: Mutex::new(file),
+            pending: Mutex::new(pending),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends a record and flushes it to disk.
+    pub fn record(
+        &self,
+        operation: Operation,
+        round_number: u64,
+        phase: Phase,
+        record: JournalRecord,
+    ) -> Result<()> {
+        let entry = JournalEntry {
+            timestamp_ms: current_timestamp_ms(),
+            operation,
+            round_number,
+            phase,
+            record,
+        };
+        let mut line = serde_json::to_string(&entry).map_err(|e| journal_error(&self.path, e))?;
+        line.push('\n');
+
+        {
+            let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+            file.write_all(line.as_bytes())
+                .and_then(|_| file.sync_data())
+                .map_err(|e| journal_error(&self.path, e))?;
+        }
+        track_pending(
+            &mut self.pending.lock().unwrap_or_else(|e| e.into_inner()),
+            entry,
+        );
+
+        Ok(())
+    }
+
+    /// Submitted entries whose outcome is not known yet.
+    pub fn pending(&self) -> Vec<JournalEntry> {
+        let pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries: Vec<_> = pending.values().cloned().collect();
+        entries.sort_by_key(|entry| entry.timestamp_ms);
+        entries
+    }
+
+    /// Looks up the pending transactions on-chain and records their outcome,
+    /// so a transaction submitted before a crash or a failed request is never
+    /// sent twice. Returns the number of resolved transactions.
+    ///
+    /// A transaction is only abandoned once the node answers that it does not
+    /// know it. If the node cannot be read, the error is returned and the
+    /// transaction stays pending.
+    pub async fn resolve_pending(&self, client: &AnglerfishClient) -> Result<usize> {
+        let pending = self.pending();
+        for entry in &pending {
+            let JournalRecord::Submitted { digest, .. } = entry.record else {
+                continue;
+            };
+            info!(
+                action = %entry.operation,
+                round = entry.round_number,
+                phase = ?entry.phase,
+                %digest,
+                "Resolving a pending transaction from the journal"
+            );
+
+            let record = match client.wait_for_checkpoint(digest).await? {
+                Some(_) => {
+                    let response = client
+                        .sui_client()
+                        .read_api()
+                        .get_transaction_with_options(
+                            digest,
+                            SuiTransactionBlockResponseOptions::new().with_effects(),
+                        )
+                        .await?;
+                    let effects = response
+                        .effects
+                        .ok_or_else(|| AnglerfishError::missing_effects(digest))?;
+                    JournalRecord::Executed {
+                        digest,
+                        status: effects.status().to_string(),
+                        gas_used: gas_used(effects.gas_cost_summary()),
+                    }
+                }
+                None => JournalRecord::Abandoned {
+                    digest,
+                    reason: "not found on-chain within the confirmation timeout".to_string(),
+                },
+            };
+            info!(action = %entry.operation, %digest, outcome = ?record, "Resolved pending transaction");
+            self.record(entry.operation, entry.round_number, entry.phase, record)?;
+        }
+
+        Ok(pending.len())
+    }
+}
+
+fn ends_without_newline(mut file: &File) -> std::io::Result<bool> {
+    if file.metadata()?.len() == 0 {
+        return Ok(false);
+    }
+    let mut last = [0u8];
+    file.seek(SeekFrom::End(-1))?;
+    file.read_exact(&mut last)?;
+    Ok(last[0] != b'\n')
+}
+
+fn track_pending(pending: &mut HashMap<TransactionDigest, JournalEntry>, entry: JournalEntry) {
+    match entry.record {
+        JournalRecord::Submitted { digest, .. } => {
+            pending.insert(digest, entry);
+        }
+        JournalRecord::Executed { digest, .. } | JournalRecord::Abandoned { digest, .. } => {
+            pending.remove(&digest);
+        }
+        JournalRecord::DryRun { .. } | JournalRecord::Confirmed { .. } => {}
+    }
+}
+
+/// One-line summary of the commands of a programmable transaction.
+pub fn summarize_ptb(pt: &ProgrammableTransaction) -> String {
+    let commands: Vec<String> = pt
+        .commands
+        .iter()
+        .map(|command| command.to_string())
+        .collect();
+    format!("{} inputs; {}", pt.inputs.len(), commands.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    const ROUND: u64 = 3;
+
+    fn submitted(digest: TransactionDigest) -> JournalRecord {
+        JournalRecord::Submitted {
+            digest,
+            gas_budget: 10_000_000,
+        }
+    }
+
+    #[test]
+    fn reopen_replays_the_pending_transactions() {
+        let path = std::env::temp_dir().join(format!(
+            "anglerfish-journal-{}-reopen_replays_the_pending_transactions.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let [executed, pending, abandoned, truncated] =
+            [(); 4].map(|_| TransactionDigest::random());
+        {
+            let journal = Journal::open(&path).unwrap();
+            let record = |record| {
+                journal
+                    .record(Operation::NextEntry, ROUND, Phase::Ticketing, record)
+                    .unwrap()
+            };
+
+            record(submitted(executed));
+            record(JournalRecord::Executed {
+                digest: executed,
+                status: "success".to_string(),
+                gas_used: 1_000,
+            });
+            record(JournalRecord::Confirmed {
+                digest: executed,
+                checkpoint: Some(42),
+                transition_observed: true,
+            });
+            record(submitted(pending));
+            record(submitted(abandoned));
+            record(JournalRecord::Abandoned {
+                digest: abandoned,
+                reason: "rejected".to_string(),
+            });
+            record(submitted(truncated));
+        }
+        // a crash while appending the next record
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"timestamp_ms":1,"operation":"next_en"#)
+            .unwrap();
+        drop(file);
+
+        let journal = Journal::open(&path).unwrap();
+        let replayed: HashSet<_> = journal
+            .pending()
+            .into_iter()
+            .map(|entry| match entry.record {
+                JournalRecord::Submitted { digest, .. } => digest,
+                record => panic!("expected a submitted record, got {:?}", record),
+            })
+            .collect();
+        assert_eq!(replayed, HashSet::from([pending, truncated]));
+
+        // the record following the truncated line is replayed too
+        journal
+            .record(
+                Operation::NextEntry,
+                ROUND,
+                Phase::Ticketing,
+                JournalRecord::Abandoned {
+                    digest: truncated,
+                    reason: "not found".to_string(),
+                },
+            )
+            .unwrap();
+        drop(journal);
+        let journal = Journal::open(&path).unwrap();
+        assert_eq!(journal.pending().len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom},
+    path::PathBuf,
+    time::Duration,
+};
+
+use fs2::FileExt;
+
+use crate::{
+    error::{AnglerfishError, Result},
+    helper::duration::current_timestamp_ms,
+};
+
+use super::{Lease, LeaseStore, lease_error};
+
+/// Keeps the lease as a JSON record in a local file.
+///
+/// Every read-modify-write of the record happens under an exclusive file lock,
+/// so replicas sharing the file (same host or a shared volume supporting
+/// `flock`) never both acquire the lease.
+pub struct FileLeaseStore {
+    path: PathBuf,
+}
+
+impl FileLeaseStore {
+    pub fn new(path: PathBuf) -> Self {
+        FileLeaseStore { path }
+    }
+
+    /// Runs `f` on the locked lease file and its current record.
+    async fn with_locked_file<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut File, Option<Lease>) -> Result<T> + Send + 'static,
+    {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)
+                .map_err(|e| AnglerfishError::Lease(format!("Failed to open {:?}: {}", path, e)))?;
+            file.lock_exclusive()
+                .map_err(|e| AnglerfishError::Lease(format!("Failed to lock {:?}: {}", path, e)))?;
+
+            let mut contents = String::new();
+            file.read_to_string(&mut contents).map_err(lease_error)?;
+            let lease = match contents.trim() {
+                "" => None,
+                contents => Some(serde_json::from_str(contents).map_err(|e| {
+                    AnglerfishError::Lease(format!("Invalid lease record in {:?}: {}", path, e))
+                })?),
+            };
+
+            // the lock is released when the file is closed
+            f(&mut file, lease)
+        })
+        .await
+        .map_err(lease_error)?
+    }
+}
+
+fn write_lease(file: &mut File, lease: Option<&Lease>) -> Result<()> {
+    file.set_len(0).map_err(lease_error)?;
+    file.seek(SeekFrom::Start(0)).map_err(lease_error)?;
+    if let Some(lease) = lease {
+        serde_json::to_writer(&mut *file, lease).map_err(lease_error)?;
+    }
+    file.sync_all().map_err(lease_error)
+}
+
+#[async_trait::async_trait]
+impl LeaseStore for FileLeaseStore {
+    async fn try_acquire(&self, holder: &str, ttl: Duration) -> Result<Lease> {
+        let holder = holder.to_string();
+        self.with_locked_file(move |file, lease| {
+            let now_ms = current_timestamp_ms();
+            match lease {
+                Some(lease) if lease.holder != holder && lease.expires_at_ms > now_ms => Ok(lease),
+                _ => {
+                    let lease = Lease {
+                        holder,
+                        expires_at_ms: now_ms + ttl.as_millis() as u64,
+                    };
+                    write_lease(file, Some(&lease))?;
+                    Ok(lease)
+                }
+            }
+        })
+        .await
+    }
+
+    async fn release(&self, holder: &str) -> Result<()> {
+        let holder = holder.to_string();
+        self.with_locked_file(move |file, lease| match lease {
+            Some(lease) if lease.holder == holder => write_lease(file, None),
+            _ => Ok(()),
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn store(name: &str) -> FileLeaseStore {
+        let path = std::env::temp_dir().join(format!(
+            "anglerfish-lease-{}-{}.json",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_file(&path);
+        FileLeaseStore::new(path)
+    }
+
+    #[tokio::test]
+    async fn only_one_holder_acquires() {
+        let store = store("only_one_holder_acquires");
+
+        let lease = store.try_acquire("a", TTL).await.unwrap();
+        assert_eq!(lease.holder, "a");
+        assert_eq!(store.try_acquire("b", TTL).await.unwrap(), lease);
+
+        // the holder renews its lease
+        let renewed = store.try_acquire("a", TTL).await.unwrap();
+        assert_eq!(renewed.holder, "a");
+        assert!(renewed.expires_at_ms >= lease.expires_at_ms);
+        assert_eq!(store.try_acquire("b", TTL).await.unwrap(), renewed);
+
+        let _ = std::fs::remove_file(&store.path);
+    }
+
+    #[tokio::test]
+    async fn contending_holders_never_both_acquire() {
+        let store = std::sync::Arc::new(store("contending_holders_never_both_acquire"));
+
+        let attempts = (0..8).map(|i| {
+            let store = store.clone();
+            tokio::spawn(async move { store.try_acquire(&format!("holder-{}", i), TTL).await })
+        });
+        let mut holders = vec![];
+        for attempt in attempts {
+            holders.push(attempt.await.unwrap().unwrap().holder);
+        }
+        holders.dedup();
+        assert_eq!(holders.len(), 1, "{:?}", holders);
+
+        let _ = std::fs::remove_file(&store.path);
+    }
+
+    #[tokio::test]
+    async fn expired_lease_is_taken_over() {
+        let store = store("expired_lease_is_taken_over");
+
+        store
+            .try_acquire("a", Duration::from_millis(50))
+            .await
+            .unwrap();
+        assert_eq!(store.try_acquire("b", TTL).await.unwrap().holder, "a");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(store.try_acquire("b", TTL).await.unwrap().holder, "b");
+        assert_eq!(store.try_acquire("a", TTL).await.unwrap().holder, "b");
+
+        let _ = std::fs::remove_file(&store.path);
+    }
+
+    #[tokio::test]
+    async fn only_the_holder_releases() {
+        let store = store("only_the_holder_releases");
+
+        store.try_acquire("a", TTL).await.unwrap();
+        store.release("b").await.unwrap();
+        assert_eq!(store.try_acquire("b", TTL).await.unwrap().holder, "a");
+
+        store.release("a").await.unwrap();
+        assert_eq!(store.try_acquire("b", TTL).await.unwrap().holder, "b");
+
+        let _ = std::fs::remove_file(&store.path);
+    }
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{
+    error::{AnglerfishError, Result},
+    helper::auth::{bearer_token_from_env, with_bearer_auth},
+};
+
+use super::{Lease, LeaseStore, lease_error};
+
+/// Keeps the lease in an HTTP lease service, e.g. a thin wrapper around a
+/// key-value store with compare-and-set.
+///
+/// Protocol:
+/// - `POST {url}/leases/{name}/acquire` with `{"holder": "..", "ttl_ms": 30000}`
+///   acquires or renews the lease when it is free, expired or already owned by
+///   `holder`, and answers the current lease `{"holder": "..", "expires_at_ms": ..}`.
+/// - `POST {url}/leases/{name}/release` with `{"holder": ".."}` frees the lease
+///   if `holder` owns it.
+///
+/// Both requests carry the token read from `auth_token_env`, if set.
+pub struct HttpLeaseStore {
+    http_client: reqwest::Client,
+    url: String,
+    auth_token: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AcquireRequest<'a> {
+    holder: &'a str,
+    ttl_ms: u64,
+}
+
+#[derive(Serialize)]
+struct ReleaseRequest<'a> {
+    holder: &'a str,
+}
+
+impl HttpLeaseStore {
+    pub fn new(url: Option<&str>, lease_name: &str, auth_token_env: Option<&str>) -> Result<Self> {
+        let url = url.ok_or_else(|| {
+            AnglerfishError::Lease("`url` is required by the http lease backend".to_string())
+        })?;
+        let auth_token = bearer_token_from_env(auth_token_env).map_err(AnglerfishError::Lease)?;
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(lease_error)?;
+
+        Ok(HttpLeaseStore {
+            http_client,
+            url: format!("{}/leases/{}", url.trim_end_matches('/'), lease_name),
+            auth_token,
+        })
+    }
+
+    async fn post(&self, action: &str, body: &impl Serialize) -> Result<reqwest::Response> {
+        let http_request = self
+            .http_client
+            .post(format!("{}/{}", self.url, action))
+            .json(body);
+
+        with_bearer_auth(http_request, self.auth_token.as_deref())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AnglerfishError::Lease(format!("Lease {} request failed: {}", action, e)))
+    }
+}
+
+#[async_trait::async_trait]
+impl LeaseStore for HttpLeaseStore {
+    async fn try_acquire(&self, holder: &str, ttl: Duration) -> Result<Lease> {
+        let request = AcquireRequest {
+            holder,
+            ttl_ms: ttl.as_millis() as u64,
+        };
+        self.post("acquire", &request)
+            .await?
+            .json()
+            .await
+            .map_err(|e| {
+                AnglerfishError::Lease(format!("Lease service returned an invalid response: {}", e))
+            })
+    }
+
+    async fn release(&self, holder: &str) -> Result<()> {
+        self.post("release", &ReleaseRequest { holder }).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,305 @@
+use std::{
+    fs,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::{
+    task::JoinHandle,
+    time::{Instant, sleep},
+};
+use tracing::{info, warn};
+
+use crate::{
+    config::{self, LeaseBackend},
+    error::{AnglerfishError, Result},
+};
+
+pub mod file;
+pub mod http;
+
+/// Lease record of a lease store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lease {
+    pub holder: String,
+    /// Expiry of the lease according to the clock of the store.
+    pub expires_at_ms: u64,
+}
+
+/// Shared store of the leader lease.
+///
+/// Only one holder owns a lease at a time. A lease which is not renewed before
+/// its expiry can be acquired by any other holder.
+#[async_trait::async_trait]
+pub trait LeaseStore: Send + Sync {
+    /// Acquires the lease for `holder` if it is free or expired, or renews it
+    /// if `holder` already owns it. Returns the current lease, which belongs to
+    /// another holder if the acquisition failed.
+    async fn try_acquire(&self, holder: &str, ttl: Duration) -> Result<Lease>;
+
+    /// Releases the lease if `holder` owns it.
+    async fn release(&self, holder: &str) -> Result<()>;
+}
+
+/// Active/passive coordination of the iterator replicas.
+///
+/// The iterator cap is an owned object, so two replicas submitting at once
+/// would equivocate it. Only the replica holding the lease submits, the others
+/// stand by and take over once the lease expires.
+pub struct LeaderElector {
+    store: Option<Box<dyn LeaseStore>>,
+    holder: String,
+    ttl: Duration,
+    renew_interval: Duration,
+    state: Mutex<LeaseState>,
+}
+
+#[derive(Default)]
+struct LeaseState {
+    // Local expiry of our lease, counted from the start of the last successful
+    // acquisition so it never outlives the lease of the store.
+    expires_at: Option<Instant>,
+    current: Option<Lease>,
+}
+
+impl LeaderElector {
+    /// Builds the elector selected by the `[leader]` section of the configuration.
+    pub fn from_config(config: &config::Leader) -> Result<Self> {
+        let store: Option<Box<dyn LeaseStore>> = match config.backend {
+            LeaseBackend::None => None,
+            LeaseBackend::File => Some(Box::new(file::FileLeaseStore::new(config.path.clone()))),
+            LeaseBackend::Http => Some(Box::new(http::HttpLeaseStore::new(
+                config.url.as_deref(),
+                &config.lease_name,
+                config.auth_token_env.as_deref(),
+            )?)),
+        };
+        let holder = config.holder_id.clone().unwrap_or_else(default_holder_id);
+
+        Ok(LeaderElector {
+            store,
+            holder,
+            ttl: Duration::from_secs(config.ttl_secs),
+            renew_interval: Duration::from_secs(config.renew_interval_secs),
+            state: Mutex::new(LeaseState::default()),
+        })
+    }
+
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    pub fn renew_interval(&self) -> Duration {
+        self.renew_interval
+    }
+
+    fn state(&self) -> MutexGuard<'_, LeaseState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Last lease read from the store, `None` without coordination.
+    pub fn current_lease(&self) -> Option<Lease> {
+        self.state().current.clone()
+    }
+
+    /// Acquires or renews the lease. Returns true if this replica is the leader.
+    pub async fn acquire(&self) -> Result<bool> {
+        let Some(store) = &self.store else {
+            return Ok(true);
+        };
+
+        let started_at = Instant::now();
+        // on error the previous lease is kept, `is_leader` stops relying on
+        // it once its local expiry is near
+        let lease = store.try_acquire(&self.holder, self.ttl).await?;
+
+        {
+            let mut state = self.state();
+            if lease.holder == self.holder {
+                if state.expires_at.is_none() {
+                    info!(holder = %self.holder, "Acquired the leader lease");
+                }
+                let expires_at = started_at + self.ttl;
+                state.expires_at = Some(state.expires_at.map_or(expires_at, |e| e.max(expires_at)));
+            } else if state.expires_at.take().is_some() {
+                warn!(holder = %lease.holder, "Lost the leader lease");
+            }
+            state.current = Some(lease);
+        }
+
+        Ok(self.is_leader())
+    }
+
+    /// Returns true if the lease is held for at least one more renew interval,
+    /// so a transaction submitted now cannot overlap with a new leader.
+    pub fn is_leader(&self) -> bool {
+        match (&self.store, self.state().expires_at) {
+            (None, _) => true,
+            (Some(_), Some(expires_at)) => Instant::now() + self.renew_interval < expires_at,
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Renews or tries to acquire the lease every renew interval until the
+    /// returned task is aborted, so long waits and slow submissions never let
+    /// the lease lapse. Returns `None` without coordination.
+    pub fn spawn_renewal(self: &Arc<Self>) -> Option<JoinHandle<()>> {
+        self.store.as_ref()?;
+        let leader = self.clone();
+        Some(tokio::spawn(async move {
+            loop {
+                sleep(leader.renew_interval).await;
+                if let Err(e) = leader.acquire().await {
+                    warn!(error = %e, "Failed to renew the leader lease");
+                }
+            }
+        }))
+    }
+
+    /// Releases the lease so a standby can take over right away.
+    pub async fn release(&self) -> Result<()> {
+        let held = self.state().expires_at.take().is_some();
+        if let (Some(store), true) = (&self.store, held) {
+            store.release(&self.holder).await?;
+            info!(holder = %self.holder, "Released the leader lease");
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn lease_error(e: impl std::fmt::Display) -> AnglerfishError {
+    AnglerfishError::Lease(e.to_string())
+}
+
+fn default_holder_id() -> String {
+    let hostname = std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|hostname| hostname.trim().to_string())
+        .filter(|hostname| !hostname.is_empty())
+        .unwrap_or_else(|| "anglerfish-iterator".to_string());
+    format!("{}-{}", hostname, std::process::id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(30);
+    const RENEW_INTERVAL: Duration = Duration::from_secs(10);
+
+    /// In-memory store shared by the electors of a test. The lease never
+    /// expires on its own, tests release it instead.
+    #[derive(Clone, Default)]
+    struct MemoryLeaseStore {
+        lease: Arc<Mutex<Option<Lease>>>,
+        unavailable: Arc<Mutex<bool>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LeaseStore for MemoryLeaseStore {
+        async fn try_acquire(&self, holder: &str, ttl: Duration) -> Result<Lease> {
+            if *self.unavailable.lock().unwrap() {
+                return Err(AnglerfishError::Lease("unavailable".to_string()));
+            }
+            let mut lease = self.lease.lock().unwrap();
+            match &*lease {
+                Some(current) if current.holder != holder => Ok(current.clone()),
+                _ => {
+                    let acquired = Lease {
+                        holder: holder.to_string(),
+                        expires_at_ms: ttl.as_millis() as u64,
+                    };
+                    *lease = Some(acquired.clone());
+                    Ok(acquired)
+                }
+            }
+        }
+
+        async fn release(&self, holder: &str) -> Result<()> {
+            let mut lease = self.lease.lock().unwrap();
+            if lease.as_ref().is_some_and(|lease| lease.holder == holder) {
+                *lease = None;
+            }
+            Ok(())
+        }
+    }
+
+    fn elector(store: &MemoryLeaseStore, holder: &str) -> LeaderElector {
+        LeaderElector {
+            store: Some(Box::new(store.clone())),
+            holder: holder.to_string(),
+            ttl: TTL,
+            renew_interval: RENEW_INTERVAL,
+            state: Mutex::new(LeaseState::default()),
+        }
+    }
+
+    #[tokio::test]
+    async fn contending_electors() {
+        let store = MemoryLeaseStore::default();
+        let a = elector(&store, "a");
+        let b = elector(&store, "b");
+
+        assert!(a.acquire().await.unwrap());
+        assert!(!b.acquire().await.unwrap());
+        assert!(a.is_leader());
+        assert!(!b.is_leader());
+        assert_eq!(b.current_lease().unwrap().holder, "a");
+
+        // a standby releasing does not touch the lease
+        b.release().await.unwrap();
+        assert!(a.acquire().await.unwrap());
+
+        a.release().await.unwrap();
+        assert!(!a.is_leader());
+        assert!(b.acquire().await.unwrap());
+        assert!(!a.acquire().await.unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn is_leader_turns_false_within_the_last_renew_interval() {
+        let store = MemoryLeaseStore::default();
+        let leader = elector(&store, "a");
+        assert!(leader.acquire().await.unwrap());
+
+        tokio::time::advance(TTL - RENEW_INTERVAL - Duration::from_millis(1)).await;
+        assert!(leader.is_leader());
+        tokio::time::advance(Duration::from_millis(1)).await;
+        assert!(!leader.is_leader());
+
+        // renewing extends the lease
+        assert!(leader.acquire().await.unwrap());
+        assert!(leader.is_leader());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_renewal_keeps_the_lease_until_near_expiry() {
+        let store = MemoryLeaseStore::default();
+        let leader = elector(&store, "a");
+        assert!(leader.acquire().await.unwrap());
+
+        *store.unavailable.lock().unwrap() = true;
+        tokio::time::advance(RENEW_INTERVAL).await;
+        assert!(leader.acquire().await.is_err());
+        assert!(leader.is_leader());
+        tokio::time::advance(RENEW_INTERVAL).await;
+        assert!(!leader.is_leader());
+    }
+
+    #[tokio::test]
+    async fn without_store_always_leads() {
+        let leader = LeaderElector {
+            store: None,
+            holder: "a".to_string(),
+            ttl: TTL,
+            renew_interval: RENEW_INTERVAL,
+            state: Mutex::new(LeaseState::default()),
+        };
+        assert!(leader.acquire().await.unwrap());
+        assert!(leader.is_leader());
+        assert!(leader.current_lease().is_none());
+        assert!(Arc::new(leader).spawn_renewal().is_none());
+    }
+}
@@ -1,3 +1,11 @@
 pub mod config;
+pub mod error;
+pub mod health;
 pub mod helper;
+pub mod journal;
+pub mod leader;
+pub mod metrics;
+pub mod notifier;
 pub mod protocols;
+pub mod server;
+pub mod signer;
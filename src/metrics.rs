@@ -0,0 +1,210 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use prometheus::{
+    Gauge, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
+};
+
+use crate::{
+    helper::duration::current_timestamp_ms,
+    protocols::anglerfish::types::phase_info::{Phase, PhaseInfo},
+};
+
+const PHASES: [Phase; 6] = [
+    Phase::Uninitialized,
+    Phase::LiquidityProviding,
+    Phase::Ticketing,
+    Phase::Drawing,
+    Phase::Distributing,
+    Phase::Settling,
+];
+
+/// Prometheus metrics of the iterator.
+///
+/// Values are only updated from data the `AnglerfishClient` fetches anyway,
+/// the metrics never trigger RPC requests of their own.
+pub struct Metrics {
+    registry: Registry,
+    phase: IntGaugeVec,
+    round_number: IntGauge,
+    seconds_until_deadline: Gauge,
+    seconds_overdue: Gauge,
+    transition_attempts: IntCounterVec,
+    transition_successes: IntCounterVec,
+    transition_failures: IntCounterVec,
+    dry_run_aborts: IntCounterVec,
+    gas_spent_mist: IntCounter,
+    signer_balance_mist: IntGauge,
+    rpc_duration_seconds: HistogramVec,
+    rpc_errors: IntCounterVec,
+    // Deadline of the last phase read, 0 if the phase is not time-bound. The
+    // deadline gauges are derived from it on every scrape.
+    deadline_ms: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new_custom(Some("anglerfish".to_string()), None)?;
+
+        let phase = IntGaugeVec::new(
+            Opts::new("phase", "Current phase, 1 for the active phase"),
+            &["phase"],
+        )?;
+        let round_number = IntGauge::new("round_number", "Current round number")?;
+        let seconds_until_deadline = Gauge::new(
+            "phase_seconds_until_deadline",
+            "Seconds until the deadline of the current phase",
+        )?;
+        let seconds_overdue = Gauge::new(
+            "phase_seconds_overdue",
+            "Seconds elapsed since the deadline of the current phase",
+        )?;
+        let transition_attempts = IntCounterVec::new(
+            Opts::new(
+                "transition_attempts_total",
+                "Phase transitions submitted after a successful dry run",
+            ),
+            &["phase"],
+        )?;
+        let transition_successes = IntCounterVec::new(
+            Opts::new(
+                "transition_successes_total",
+                "Submitted phase transitions executed successfully",
+            ),
+            &["phase"],
+        )?;
+        let transition_failures = IntCounterVec::new(
+            Opts::new(
+                "transition_failures_total",
+                "Submitted phase transitions which failed or have an unknown outcome",
+            ),
+            &["phase"],
+        )?;
+        let dry_run_aborts = IntCounterVec::new(
+            Opts::new(
+                "dry_run_aborts_total",
+                "Phase transitions not submitted because their dry run failed",
+            ),
+            &["phase"],
+        )?;
+        let gas_spent_mist = IntCounter::new(
+            "gas_spent_mist_total",
+            "Net gas spent by executed transactions, in MIST",
+        )?;
+        let signer_balance_mist = IntGauge::new(
+            "signer_balance_mist",
+            "SUI balance of the signer as of the last gas coin selection, in MIST",
+        )?;
+        let rpc_duration_seconds = HistogramVec::new(
+            HistogramOpts::new("rpc_duration_seconds", "Latency of the RPC requests"),
+            &["method"],
+        )?;
+        let rpc_errors = IntCounterVec::new(
+            Opts::new("rpc_errors_total", "Failed RPC requests"),
+            &["method"],
+        )?;
+
+        registry.register(Box::new(phase.clone()))?;
+        registry.register(Box::new(round_number.clone()))?;
+        registry.register(Box::new(seconds_until_deadline.clone()))?;
+        registry.register(Box::new(seconds_overdue.clone()))?;
+        registry.register(Box::new(transition_attempts.clone()))?;
+        registry.register(Box::new(transition_successes.clone()))?;
+        registry.register(Box::new(transition_failures.clone()))?;
+        registry.register(Box::new(dry_run_aborts.clone()))?;
+        registry.register(Box::new(gas_spent_mist.clone()))?;
+        registry.register(Box::new(signer_balance_mist.clone()))?;
+        registry.register(Box::new(rpc_duration_seconds.clone()))?;
+        registry.register(Box::new(rpc_errors.clone()))?;
+
+        Ok(Metrics {
+            registry,
+            phase,
+            round_number,
+            seconds_until_deadline,
+            seconds_overdue,
+            transition_attempts,
+            transition_successes,
+            transition_failures,
+            dry_run_aborts,
+            gas_spent_mist,
+            signer_balance_mist,
+            rpc_duration_seconds,
+            rpc_errors,
+            deadline_ms: AtomicU64::new(0),
+        })
+    }
+
+    pub fn observe_phase(&self, phase_info: &PhaseInfo) {
+        for phase in PHASES {
+            let active = phase == phase_info.current_phase;
+            self.phase
+                .with_label_values(&[&format!("{:?}", phase)])
+                .set(active as i64);
+        }
+        self.round_number
+            .set(phase_info.current_round_number as i64);
+        self.deadline_ms.store(
+            phase_info.current_phase_deadline().unwrap_or_default(),
+            Ordering::Relaxed,
+        );
+    }
+
+    pub fn transition_attempt(&self, phase: Phase) {
+        self.transition_attempts
+            .with_label_values(&[&format!("{:?}", phase)])
+            .inc();
+    }
+
+    pub fn transition_result(&self, phase: Phase, success: bool) {
+        let counter = match success {
+            true => &self.transition_successes,
+            false => &self.transition_failures,
+        };
+        counter.with_label_values(&[&format!("{:?}", phase)]).inc();
+    }
+
+    pub fn dry_run_abort(&self, phase: Phase) {
+        self.dry_run_aborts
+            .with_label_values(&[&format!("{:?}", phase)])
+            .inc();
+    }
+
+    pub fn gas_spent(&self, mist: u64) {
+        self.gas_spent_mist.inc_by(mist);
+    }
+
+    pub fn signer_balance(&self, mist: u64) {
+        self.signer_balance_mist.set(mist as i64);
+    }
+
+    pub fn observe_rpc(&self, method: &str, duration: Duration, success: bool) {
+        self.rpc_duration_seconds
+            .with_label_values(&[method])
+            .observe(duration.as_secs_f64());
+        if !success {
+            self.rpc_errors.with_label_values(&[method]).inc();
+        }
+    }
+
+    /// Encodes all the metrics in the Prometheus text format.
+    pub fn gather(&self) -> Result<String, prometheus::Error> {
+        let deadline_ms = self.deadline_ms.load(Ordering::Relaxed);
+        let now_ms = current_timestamp_ms();
+        let (until_deadline_ms, overdue_ms) = match deadline_ms {
+            0 => (0, 0),
+            deadline_ms => (
+                deadline_ms.saturating_sub(now_ms),
+                now_ms.saturating_sub(deadline_ms),
+            ),
+        };
+        self.seconds_until_deadline
+            .set(until_deadline_ms as f64 / 1000.0);
+        self.seconds_overdue.set(overdue_ms as f64 / 1000.0);
+
+        TextEncoder::new().encode_to_string(&self.registry.gather())
+    }
+}
@@ -0,0 +1,372 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::{
+    config,
+    error::Result,
+    helper::duration::{current_timestamp_ms, format_duration_ms},
+    protocols::anglerfish::types::phase_info::{Phase, PhaseInfo},
+};
+
+pub mod webhook;
+
+/// Events which can raise an alert. Each one is rate limited on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    TransitionFailures,
+    PhaseOverdue,
+    LowBalance,
+    RoundCompleted,
+    Test,
+}
+
+impl fmt::Display for AlertKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AlertKind::TransitionFailures => "transition_failures",
+            AlertKind::PhaseOverdue => "phase_overdue",
+            AlertKind::LowBalance => "low_balance",
+            AlertKind::RoundCompleted => "round_completed",
+            AlertKind::Test => "test",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub event: AlertKind,
+    pub message: String,
+    pub round_number: Option<u64>,
+    pub phase: Option<Phase>,
+    pub timestamp_ms: u64,
+}
+
+impl Alert {
+    pub fn new(event: AlertKind, message: String) -> Self {
+        Alert {
+            event,
+            message,
+            round_number: None,
+            phase: None,
+            timestamp_ms: current_timestamp_ms(),
+        }
+    }
+
+    pub fn with_phase(mut self, phase_info: &PhaseInfo) -> Self {
+        self.round_number = Some(phase_info.current_round_number);
+        self.phase = Some(phase_info.current_phase);
+        self
+    }
+}
+
+/// Destination of the alerts.
+#[async_trait::async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, alert: &Alert) -> Result<()>;
+}
+
+/// Decides when to alert and fans the alerts out to the notifiers.
+///
+/// Alerts of the same event are sent at most once per rate limit interval,
+/// delivery failures are logged and never interrupt the iterator.
+pub struct Alerter {
+    notifiers: Vec<Box<dyn Notifier>>,
+    rate_limit: Duration,
+    failure_threshold: u32,
+    overdue_threshold_ms: u64,
+    min_balance_mist: Option<u64>,
+    round_completed: bool,
+    consecutive_failures: Mutex<u32>,
+    last_sent: Mutex<HashMap<AlertKind, Instant>>,
+}
+
+impl Alerter {
+    /// Builds the alerter of the `[alerts]` section of the configuration.
+    pub fn from_config(config: &config::Alerts) -> Result<Self> {
+        let notifiers = config
+            .webhooks
+            .iter()
+            .map(|webhook| {
+                webhook::WebhookNotifier::new(webhook.kind, &webhook.url)
+                    .map(|notifier| Box::new(notifier) as Box<dyn Notifier>)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Alerter::new(notifiers, config))
+    }
+
+    pub fn new(notifiers: Vec<Box<dyn Notifier>>, config: &config::Alerts) -> Self {
+        Alerter {
+            notifiers,
+            rate_limit: Duration::from_secs(config.rate_limit_secs),
+            failure_threshold: config.failure_threshold,
+            overdue_threshold_ms: config.overdue_threshold_secs * 1000,
+            min_balance_mist: config.min_balance_mist,
+            round_completed: config.round_completed,
+            consecutive_failures: Mutex::new(0),
+            last_sent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns true if the signer balance should be checked.
+    pub fn watches_balance(&self) -> bool {
+        !self.notifiers.is_empty() && self.min_balance_mist.is_some()
+    }
+
+    pub fn tick_succeeded(&self) {
+        *self
+            .consecutive_failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = 0;
+    }
+
+    pub async fn tick_failed(&self, error: &str) {
+        let failures = {
+            let mut failures = self
+                .consecutive_failures
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            *failures += 1;
+            *failures
+        };
+        if failures >= self.failure_threshold {
+            let message = format!("{} consecutive failures, last one: {}", failures, error);
+            self.send(Alert::new(AlertKind::TransitionFailures, message))
+                .await;
+        }
+    }
+
+    /// Alerts if the current phase has been due for longer than the threshold.
+    pub async fn check_phase(&self, phase_info: &PhaseInfo, now_ms: u64) {
+        let Some(due_at) = due_at(phase_info) else {
+            return;
+        };
+        let overdue_ms = now_ms.saturating_sub(due_at);
+        if overdue_ms > 0 && overdue_ms >= self.overdue_threshold_ms {
+            let message = format!(
+                "{:?} phase of round {} is overdue by {}",
+                phase_info.current_phase,
+                phase_info.current_round_number,
+                format_duration_ms(overdue_ms)
+            );
+            self.send(Alert::new(AlertKind::PhaseOverdue, message).with_phase(phase_info))
+                .await;
+        }
+    }
+
+    pub async fn check_balance(&self, balance_mist: u128) {
+        let Some(min_balance_mist) = self.min_balance_mist else {
+            return;
+        };
+        if balance_mist < min_balance_mist as u128 {
+            let message = format!(
+                "Signer balance is {} MIST, below the threshold of {} MIST",
+                balance_mist, min_balance_mist
+            );
+            self.send(Alert::new(AlertKind::LowBalance, message)).await;
+        }
+    }
+
+    /// Notifies the completion of a round, if enabled.
+    pub async fn round_completed(&self, phase_info: &PhaseInfo) {
+        if !self.round_completed {
+            return;
+        }
+        let message = format!(
+            "Round {} completed, round {} started",
+            phase_info.current_round_number.saturating_sub(1),
+            phase_info.current_round_number
+        );
+        self.send(Alert::new(AlertKind::RoundCompleted, message).with_phase(phase_info))
+            .await;
+    }
+
+    /// Sends a test alert to every notifier, returning the first delivery error.
+    pub async fn test(&self) -> Result<()> {
+        let alert = Alert::new(
+            AlertKind::Test,
+            "Test alert from the Anglerfish iterator".to_string(),
+        );
+        for notifier in &self.notifiers {
+            notifier.notify(&alert).await?;
+        }
+        Ok(())
+    }
+
+    async fn send(&self, alert: Alert) {
+        if self.notifiers.is_empty() || self.is_rate_limited(alert.event) {
+            return;
+        }
+
+        info!(event = %alert.event, message = %alert.message, "Sending alert");
+        for notifier in &self.notifiers {
+            if let Err(e) = notifier.notify(&alert).await {
+                warn!(event = %alert.event, error = %e, "Failed to send alert");
+            }
+        }
+    }
+
+    // Returns true if `kind` was sent less than one rate limit interval ago,
+    // otherwise records it as sent now.
+    fn is_rate_limited(&self, kind: AlertKind) -> bool {
+        let mut last_sent = self.last_sent.lock().unwrap_or_else(|e| e.into_inner());
+        match last_sent.get(&kind) {
+            Some(sent_at) if sent_at.elapsed() < self.rate_limit => true,
+            _ => {
+                last_sent.insert(kind, Instant::now());
+                false
+            }
+        }
+    }
+}
+
+/// Timestamp (ms) from which the current phase can be advanced. Phases which
+/// are not time-bound are due as soon as they start.
+fn due_at(phase_info: &PhaseInfo) -> Option<u64> {
+    match phase_info.current_phase {
+        Phase::Uninitialized => None,
+        Phase::LiquidityProviding | Phase::Ticketing => phase_info.current_phase_deadline(),
+        Phase::Drawing | Phase::Distributing | Phase::Settling => Some(phase_info.current_phase_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::error::AnglerfishError;
+
+    /// Records the alerts it receives, or fails every delivery.
+    #[derive(Clone, Default)]
+    struct RecordingNotifier {
+        alerts: Arc<Mutex<Vec<Alert>>>,
+        failing: bool,
+    }
+
+    impl RecordingNotifier {
+        fn events(&self) -> Vec<AlertKind> {
+            let alerts = self.alerts.lock().unwrap();
+            alerts.iter().map(|alert| alert.event).collect()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn notify(&self, alert: &Alert) -> Result<()> {
+            if self.failing {
+                return Err(AnglerfishError::Notifier("unreachable".to_string()));
+            }
+            self.alerts.lock().unwrap().push(alert.clone());
+            Ok(())
+        }
+    }
+
+    fn alerter(notifiers: Vec<RecordingNotifier>, config: config::Alerts) -> Alerter {
+        let notifiers = notifiers
+            .into_iter()
+            .map(|notifier| Box::new(notifier) as Box<dyn Notifier>)
+            .collect();
+        Alerter::new(notifiers, &config)
+    }
+
+    #[tokio::test]
+    async fn alerts_after_consecutive_failures() {
+        let notifier = RecordingNotifier::default();
+        let alerter = alerter(
+            vec![notifier.clone()],
+            config::Alerts {
+                rate_limit_secs: 0,
+                failure_threshold: 3,
+                ..Default::default()
+            },
+        );
+
+        alerter.tick_failed("timeout").await;
+        alerter.tick_failed("timeout").await;
+        assert!(notifier.events().is_empty());
+        alerter.tick_failed("move abort").await;
+        assert_eq!(notifier.events(), [AlertKind::TransitionFailures]);
+        assert_eq!(
+            notifier.alerts.lock().unwrap()[0].message,
+            "3 consecutive failures, last one: move abort"
+        );
+
+        // a success resets the count
+        alerter.tick_succeeded();
+        alerter.tick_failed("timeout").await;
+        alerter.tick_failed("timeout").await;
+        assert_eq!(notifier.events().len(), 1);
+        alerter.tick_failed("timeout").await;
+        assert_eq!(notifier.events().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rate_limits_each_event_on_its_own() {
+        let notifier = RecordingNotifier::default();
+        let alerter = alerter(
+            vec![notifier.clone()],
+            config::Alerts {
+                rate_limit_secs: 900,
+                failure_threshold: 1,
+                min_balance_mist: Some(1_000),
+                ..Default::default()
+            },
+        );
+
+        for _ in 0..3 {
+            alerter.tick_failed("timeout").await;
+            alerter.check_balance(10).await;
+        }
+        alerter.check_balance(1_000).await;
+        assert_eq!(
+            notifier.events(),
+            [AlertKind::TransitionFailures, AlertKind::LowBalance]
+        );
+    }
+
+    #[tokio::test]
+    async fn delivery_failures_do_not_stop_other_notifiers() {
+        let failing = RecordingNotifier {
+            failing: true,
+            ..Default::default()
+        };
+        let notifier = RecordingNotifier::default();
+        let alerter = alerter(
+            vec![failing, notifier.clone()],
+            config::Alerts {
+                failure_threshold: 1,
+                ..Default::default()
+            },
+        );
+
+        alerter.tick_failed("timeout").await;
+        assert_eq!(notifier.events(), [AlertKind::TransitionFailures]);
+        // the test alert reports the delivery error instead
+        assert!(alerter.test().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn no_alert_without_notifiers() {
+        let alerter = alerter(
+            vec![],
+            config::Alerts {
+                min_balance_mist: Some(1_000),
+                ..Default::default()
+            },
+        );
+        assert!(!alerter.watches_balance());
+        alerter.tick_failed("timeout").await;
+        alerter.check_balance(0).await;
+        assert!(alerter.last_sent.lock().unwrap().is_empty());
+    }
+}
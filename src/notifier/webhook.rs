@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use serde_json::{Value, json};
+
+use crate::{
+    config::WebhookKind,
+    error::{AnglerfishError, Result},
+};
+
+use super::{Alert, Notifier};
+
+/// Discord rejects messages longer than this.
+const DISCORD_MAX_CONTENT_CHARS: usize = 2000;
+
+/// Posts alerts to an HTTP webhook.
+///
+/// - `generic`: the alert as JSON, `{"event", "message", "round_number", "phase", "timestamp_ms"}`.
+/// - `slack`: `{"text": ".."}`, as expected by Slack incoming webhooks.
+/// - `discord`: `{"content": ".."}`, as expected by Discord webhooks.
+pub struct WebhookNotifier {
+    http_client: reqwest::Client,
+    kind: WebhookKind,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(kind: WebhookKind, url: &str) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| AnglerfishError::Notifier(e.to_string()))?;
+
+        Ok(WebhookNotifier {
+            http_client,
+            kind,
+            url: url.to_string(),
+        })
+    }
+
+    fn payload(&self, alert: &Alert) -> Value {
+        match self.kind {
+            WebhookKind::Generic => json!(alert),
+            WebhookKind::Slack => json!({ "text": text(alert) }),
+            WebhookKind::Discord => {
+                json!({ "content": text(alert).chars().take(DISCORD_MAX_CONTENT_CHARS).collect::<String>() })
+            }
+        }
+    }
+}
+
+/// Single line rendering of an alert for chat webhooks.
+fn text(alert: &Alert) -> String {
+    match (alert.round_number, alert.phase) {
+        (Some(round_number), Some(phase)) => format!(
+            "[anglerfish] {} (round {}, {:?}): {}",
+            alert.event, round_number, phase, alert.message
+        ),
+        _ => format!("[anglerfish] {}: {}", alert.event, alert.message),
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, alert: &Alert) -> Result<()> {
+        self.http_client
+            .post(&self.url)
+            .json(&self.payload(alert))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AnglerfishError::Notifier(format!("Webhook request failed: {}", e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{Json, Router, extract::Path, http::StatusCode, routing::post};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::{notifier::AlertKind, protocols::anglerfish::types::phase_info::Phase};
+
+    fn alert() -> Alert {
+        Alert {
+            event: AlertKind::PhaseOverdue,
+            message: "Drawing phase of round 7 is overdue by 10m".to_string(),
+            round_number: Some(7),
+            phase: Some(Phase::Drawing),
+            timestamp_ms: 1_700_000_000_000,
+        }
+    }
+
+    fn payload(kind: WebhookKind, alert: &Alert) -> Value {
+        WebhookNotifier::new(kind, "http://127.0.0.1")
+            .unwrap()
+            .payload(alert)
+    }
+
+    #[test]
+    fn payload_of_each_webhook_kind() {
+        let text = "[anglerfish] phase_overdue (round 7, Drawing): Drawing phase of round 7 is overdue by 10m";
+        let cases = [
+            (
+                WebhookKind::Generic,
+                json!({
+                    "event": "phase_overdue",
+                    "message": "Drawing phase of round 7 is overdue by 10m",
+                    "round_number": 7,
+                    "phase": "Drawing",
+                    "timestamp_ms": 1_700_000_000_000u64,
+                }),
+            ),
+            (WebhookKind::Slack, json!({ "text": text })),
+            (WebhookKind::Discord, json!({ "content": text })),
+        ];
+        for (kind, expected) in cases {
+            assert_eq!(payload(kind, &alert()), expected, "{:?}", kind);
+        }
+
+        let without_phase = Alert::new(AlertKind::Test, "hello".to_string());
+        assert_eq!(
+            payload(WebhookKind::Slack, &without_phase),
+            json!({ "text": "[anglerfish] test: hello" })
+        );
+    }
+
+    #[test]
+    fn discord_content_is_truncated() {
+        let long = Alert::new(AlertKind::Test, "x".repeat(3 * DISCORD_MAX_CONTENT_CHARS));
+        let content = payload(WebhookKind::Discord, &long)["content"]
+            .as_str()
+            .unwrap()
+            .chars()
+            .count();
+        assert_eq!(content, DISCORD_MAX_CONTENT_CHARS);
+    }
+
+    #[tokio::test]
+    async fn notify_posts_the_payload() {
+        let received = Arc::new(Mutex::new(vec![]));
+        let app = Router::new().route(
+            "/{status}",
+            post({
+                let received = received.clone();
+                move |Path(status): Path<u16>, Json(body): Json<Value>| async move {
+                    received.lock().unwrap().push(body);
+                    StatusCode::from_u16(status).unwrap()
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let notifier = WebhookNotifier::new(WebhookKind::Slack, &format!("{}/204", url)).unwrap();
+        notifier.notify(&alert()).await.unwrap();
+        assert_eq!(
+            received.lock().unwrap().as_slice(),
+            [payload(WebhookKind::Slack, &alert())]
+        );
+
+        let notifier = WebhookNotifier::new(WebhookKind::Slack, &format!("{}/500", url)).unwrap();
+        assert!(matches!(
+            notifier.notify(&alert()).await,
+            Err(AnglerfishError::Notifier(_))
+        ));
+    }
+}
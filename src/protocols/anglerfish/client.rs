@@ -1,53 +1,159 @@
-use anyhow::{Result, anyhow};
+use std::{future::Future, sync::Arc, time::Instant};
+
 use serde_json::Value;
 use sui_sdk::{
     SuiClient,
     types::{
-        TypeTag, base_types::ObjectID, digests::TransactionDigest, dynamic_field::DynamicFieldName,
+        TypeTag,
+        base_types::{ObjectID, SuiAddress},
+        dynamic_field::DynamicFieldName,
         programmable_transaction_builder::ProgrammableTransactionBuilder,
     },
-    wallet_context::WalletContext,
 };
 
 use crate::{
     config::{self, Config},
+    error::{AnglerfishError, Result},
     helper::sui::SuiObjectBuilder,
+    journal::{Journal, JournalRecord},
+    leader::LeaderElector,
+    metrics::Metrics,
+    signer::Signer,
 };
 
 use super::{
-    ids,
+    engine::Operation,
     ptb::AnglerfishProgramableTransaction,
-    sui_client::AnglerfishSuiClient,
-    types::{FieldsExtractor, phase_info::PhaseInfo, round::Round, round_registry::RoundRegistry},
+    sui_client::{AnglerfishSuiClient, ExecutionResult},
+    types::{
+        FieldsExtractor,
+        lounge_registry::LoungeRegistry,
+        phase_info::PhaseInfo,
+        pool_registry::PoolRegistry,
+        prize_pool::PrizePool,
+        round::{Round, RoundEntry},
+        round_registry::RoundRegistry,
+    },
 };
 
 pub struct AnglerfishClient {
     sui_client: SuiClient,
-    wallet: WalletContext,
+    signer: Option<Box<dyn Signer>>,
     config: Config,
+    journal: Option<Journal>,
+    metrics: Option<Arc<Metrics>>,
+    leader: Option<Arc<LeaderElector>>,
 }
 
 impl AnglerfishClient {
-    pub fn new(sui_client: SuiClient, wallet: WalletContext, config: Config) -> Self {
+    pub fn new(sui_client: SuiClient, config: Config) -> Self {
         AnglerfishClient {
             sui_client,
-            wallet,
+            signer: None,
             config,
+            journal: None,
+            metrics: None,
+            leader: None,
         }
     }
 
+    /// Signs the iterator transactions with `signer`. A client without signer
+    /// can only read the on-chain state.
+    pub fn with_signer(mut self, signer: Box<dyn Signer>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Records every attempted transaction in `journal`.
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Updates `metrics` with the data fetched and the transactions executed.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Checks that `leader` still holds the lease right before each submission.
+    pub fn with_leader(mut self, leader: Arc<LeaderElector>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
     pub fn sui_client(&self) -> &SuiClient {
         &self.sui_client
     }
 
-    pub fn wallet(&self) -> &WalletContext {
-        &self.wallet
+    pub fn signer(&self) -> Result<&dyn Signer> {
+        self.signer
+            .as_deref()
+            .ok_or_else(|| AnglerfishError::Signer("No signer loaded".to_string()))
+    }
+
+    /// Address of the signer of the iterator transactions.
+    pub fn sender_address(&self) -> Result<SuiAddress> {
+        Ok(self.signer()?.address())
+    }
+
+    pub fn journal(&self) -> Option<&Journal> {
+        self.journal.as_ref()
+    }
+
+    pub fn metrics(&self) -> Option<&Metrics> {
+        self.metrics.as_deref()
+    }
+
+    pub fn leader(&self) -> Option<&LeaderElector> {
+        self.leader.as_deref()
+    }
+
+    /// Awaits an RPC request, recording its latency and outcome in the metrics.
+    pub(crate) async fn observe_rpc<T, E>(
+        &self,
+        method: &str,
+        request: impl Future<Output = std::result::Result<T, E>>,
+    ) -> std::result::Result<T, E> {
+        let started_at = Instant::now();
+        let result = request.await;
+        if let Some(metrics) = self.metrics() {
+            metrics.observe_rpc(method, started_at.elapsed(), result.is_ok());
+        }
+        result
+    }
+
+    /// Appends a record for `operation` to the journal, if one is configured.
+    pub(crate) fn record(
+        &self,
+        operation: Operation,
+        phase_info: &PhaseInfo,
+        record: JournalRecord,
+    ) -> Result<()> {
+        match self.journal() {
+            Some(journal) => journal.record(
+                operation,
+                phase_info.current_round_number,
+                phase_info.current_phase,
+                record,
+            ),
+            None => Ok(()),
+        }
     }
 
     pub fn config(&self) -> &Config {
         &self.config
     }
 
+    pub fn package_id(&self) -> ObjectID {
+        self.config().package_id
+    }
+
+    /// Package the Anglerfish object and event types are defined in.
+    pub fn original_package_id(&self) -> ObjectID {
+        self.config().original_package_id
+    }
+
     pub fn objects(&self) -> &config::Objects {
         &self.config().objects
     }
@@ -56,86 +162,256 @@ impl AnglerfishClient {
         self.config().pool.coin_type.to_owned()
     }
 
-    pub fn iterator_cap_id(&self) -> String {
-        self.config().iterator.cap_id.to_owned()
+    pub fn iterator_cap_id(&self) -> ObjectID {
+        self.config().iterator.cap_id
     }
 
     /// Fetch the Anglerfish objects
 
     pub async fn get_phase_info(&self) -> Result<PhaseInfo> {
         let phase_info = self
-            .sui_client()
-            .fetch_obj::<PhaseInfo>(&ids::anglerfish_phase_info_id())
+            .observe_rpc(
+                "get_phase_info",
+                self.sui_client()
+                    .fetch_obj::<PhaseInfo>(self.objects().phase_info_id),
+            )
             .await?;
+        if let Some(metrics) = self.metrics() {
+            metrics.observe_phase(&phase_info);
+        }
         Ok(phase_info)
     }
 
+    /// Total SUI balance of the signer, in MIST.
+    pub async fn get_signer_balance(&self) -> Result<u128> {
+        let balance = self
+            .observe_rpc(
+                "get_balance",
+                self.sui_client()
+                    .coin_read_api()
+                    .get_balance(self.sender_address()?, None),
+            )
+            .await?;
+        if let Some(metrics) = self.metrics() {
+            metrics.signer_balance(balance.total_balance.min(u64::MAX as u128) as u64);
+        }
+        Ok(balance.total_balance)
+    }
+
     pub async fn get_round_registry(&self) -> Result<RoundRegistry> {
         let round_registry = self
-            .sui_client()
-            .fetch_obj::<RoundRegistry>(&ids::anglerfish_round_registry_id())
+            .observe_rpc(
+                "get_round_registry",
+                self.sui_client()
+                    .fetch_obj::<RoundRegistry>(self.objects().round_registry_id),
+            )
             .await?;
         Ok(round_registry)
     }
 
+    pub async fn get_prize_pool(&self) -> Result<PrizePool> {
+        let prize_pool = self
+            .observe_rpc(
+                "get_prize_pool",
+                self.sui_client()
+                    .fetch_obj::<PrizePool>(self.objects().prize_pool_id),
+            )
+            .await?;
+        Ok(prize_pool)
+    }
+
+    pub async fn get_pool_registry(&self) -> Result<PoolRegistry> {
+        let pool_registry = self
+            .observe_rpc(
+                "get_pool_registry",
+                self.sui_client()
+                    .fetch_obj::<PoolRegistry>(self.objects().pool_registry_id),
+            )
+            .await?;
+        Ok(pool_registry)
+    }
+
+    pub async fn get_lounge_registry(&self) -> Result<LoungeRegistry> {
+        let lounge_registry = self
+            .observe_rpc(
+                "get_lounge_registry",
+                self.sui_client()
+                    .fetch_obj::<LoungeRegistry>(self.objects().lounge_registry_id),
+            )
+            .await?;
+        Ok(lounge_registry)
+    }
+
+    /// Resolves the id of the `Round` object of `round_number` in the
+    /// `RoundRegistry.rounds` table `table_id`.
     pub async fn get_round_obj_id_from_table(
         &self,
         table_id: ObjectID,
         round_number: u64,
-    ) -> Result<Round> {
+    ) -> Result<ObjectID> {
         let round_fields = self
-            .sui_client()
-            .read_api()
-            .get_dynamic_field_object(
-                table_id,
-                DynamicFieldName {
-                    type_: TypeTag::U64,
-                    value: Value::String(round_number.to_string()),
-                },
+            .observe_rpc(
+                "get_dynamic_field_object",
+                self.sui_client().read_api().get_dynamic_field_object(
+                    table_id,
+                    DynamicFieldName {
+                        type_: TypeTag::U64,
+                        value: Value::String(round_number.to_string()),
+                    },
+                ),
             )
             .await?;
-        let round = match round_fields.data {
-            Some(obj) => Round::try_from_sui_obj_data(obj)?,
+        let round_entry = match round_fields.data {
+            Some(obj) => RoundEntry::try_from_sui_obj_data(obj)?,
             _ => {
-                return Err(anyhow!("Failed to fetch round fields"));
+                return Err(AnglerfishError::ObjectDecode(format!(
+                    "Failed to fetch the fields of round {}",
+                    round_number
+                )));
             }
         };
+        Ok(round_entry.round_id)
+    }
+
+    /// Fetches the `Round` object of `round_number`.
+    pub async fn get_round(&self, round_number: u64) -> Result<Round> {
+        let round_registry = self.get_round_registry().await?;
+        let round_id = self
+            .get_round_obj_id_from_table(round_registry.rounds.id, round_number)
+            .await?;
+        let round = self
+            .observe_rpc("get_round", self.sui_client().fetch_obj::<Round>(round_id))
+            .await?;
         Ok(round)
     }
 
     // Execute Anglerfish functions
 
-    pub async fn execute_next_entry(&self) -> Result<TransactionDigest> {
+    pub async fn execute_operation(&self, operation: Operation) -> Result<ExecutionResult> {
+        match operation {
+            Operation::NextEntry => self.execute_next_entry().await,
+            Operation::Draw => self.execute_draw().await,
+            Operation::Distribute => self.execute_distribute().await,
+            Operation::StartNewRound => self.execute_start_new_round().await,
+        }
+    }
+
+    pub async fn execute_next_entry(&self) -> Result<ExecutionResult> {
+        let phase_info = self.get_phase_info().await?;
         let mut ptb = ProgrammableTransactionBuilder::new();
         self.build_next_entry(&mut ptb).await?;
-        Ok(self.execute(ptb.finish()).await?)
+        Ok(self
+            .execute(Operation::NextEntry, &phase_info, ptb.finish())
+            .await?)
     }
 
-    pub async fn execute_draw(&self) -> Result<TransactionDigest> {
+    pub async fn execute_draw(&self) -> Result<ExecutionResult> {
         let phase_info = self.get_phase_info().await?;
         let round_registry = self.get_round_registry().await?;
-        let round = self
+        let round_id = self
             .get_round_obj_id_from_table(round_registry.rounds.id, phase_info.current_round_number)
             .await?;
         let mut ptb = ProgrammableTransactionBuilder::new();
-        self.build_draw(&mut ptb, &round).await?;
-        Ok(self.execute(ptb.finish()).await?)
+        self.build_draw(&mut ptb, round_id).await?;
+        Ok(self
+            .execute(Operation::Draw, &phase_info, ptb.finish())
+            .await?)
     }
 
-    pub async fn execute_distribute(&self) -> Result<TransactionDigest> {
+    pub async fn execute_distribute(&self) -> Result<ExecutionResult> {
         let phase_info = self.get_phase_info().await?;
         let round_registry = self.get_round_registry().await?;
-        let round = self
+        let round_id = self
             .get_round_obj_id_from_table(round_registry.rounds.id, phase_info.current_round_number)
             .await?;
         let mut ptb = ProgrammableTransactionBuilder::new();
-        self.build_distribute(&mut ptb, &round).await?;
-        Ok(self.execute(ptb.finish()).await?)
+        self.build_distribute(&mut ptb, round_id).await?;
+        Ok(self
+            .execute(Operation::Distribute, &phase_info, ptb.finish())
+            .await?)
     }
 
-    pub async fn execute_start_new_round(&self) -> Result<TransactionDigest> {
+    pub async fn execute_start_new_round(&self) -> Result<ExecutionResult> {
+        let phase_info = self.get_phase_info().await?;
         let mut ptb = ProgrammableTransactionBuilder::new();
         self.build_start_new_round(&mut ptb).await?;
-        Ok(self.execute(ptb.finish()).await?)
+        Ok(self
+            .execute(Operation::StartNewRound, &phase_info, ptb.finish())
+            .await?)
+    }
+}
+
+/// Decodes the objects of the testnet deployment, which checks the Rust
+/// layouts against the BCS of the published Move structs. They need network
+/// access: `cargo test -- --ignored`.
+#[cfg(test)]
+mod testnet_tests {
+    use super::*;
+    use crate::{
+        helper::network::build_sui_client, protocols::anglerfish::purchases::AnglerfishPurchases,
+    };
+
+    async fn testnet_client() -> AnglerfishClient {
+        let config: Config = toml::from_str(
+            r#"
+            preset = "testnet"
+
+            [iterator]
+            cap_id = "0xb1d079bb4b76d5bfadd49c94ff8b5e6e66eb60cc2a7c68b4e832938298c06325"
+            "#,
+        )
+        .unwrap();
+        let sui_client = build_sui_client(&config.network).await.unwrap();
+        AnglerfishClient::new(sui_client, config)
+    }
+
+    #[tokio::test]
+    #[ignore = "reads the testnet deployment"]
+    async fn decode_testnet_round() {
+        let client = testnet_client().await;
+        let round_number = client.get_phase_info().await.unwrap().current_round_number;
+
+        let round = client.get_round(round_number).await.unwrap();
+        assert_eq!(round.round_number, round_number);
+        assert!(round.winning_ticket_index.is_none() || round.is_drawn());
+
+        let purchases = client.get_all_purchases(&round).await.unwrap();
+        assert_eq!(purchases.len() as u64, round.purchases.contents.size);
+        let mut next_ticket = 0;
+        for purchase in &purchases {
+            assert_eq!(purchase.start_index, next_ticket);
+            next_ticket = purchase.tickets().end;
+        }
+        assert_eq!(next_ticket, round.total_tickets);
+    }
+
+    #[tokio::test]
+    #[ignore = "reads the testnet deployment"]
+    async fn decode_testnet_prize_pool() {
+        let client = testnet_client().await;
+        let prize_pool = client.get_prize_pool().await.unwrap();
+        assert_eq!(prize_pool.id.id.bytes, client.objects().prize_pool_id);
+        assert!(prize_pool.ticket_price > 0);
+        assert!(prize_pool.lp_fee_bps + prize_pool.protocol_fee_bps <= 10_000);
+    }
+
+    #[tokio::test]
+    #[ignore = "reads the testnet deployment"]
+    async fn decode_testnet_pool_registry() {
+        let client = testnet_client().await;
+        let pool_registry = client.get_pool_registry().await.unwrap();
+        assert_eq!(pool_registry.id.id.bytes, client.objects().pool_registry_id);
+    }
+
+    #[tokio::test]
+    #[ignore = "reads the testnet deployment"]
+    async fn decode_testnet_lounge_registry() {
+        let client = testnet_client().await;
+        let lounge_registry = client.get_lounge_registry().await.unwrap();
+        assert_eq!(
+            lounge_registry.id.id.bytes,
+            client.objects().lounge_registry_id
+        );
+        assert_ne!(lounge_registry.lounges.id, ObjectID::ZERO);
     }
 }
@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use sui_sdk::{
+    rpc_types::SuiTransactionBlockResponseOptions,
+    types::{digests::TransactionDigest, messages_checkpoint::CheckpointSequenceNumber},
+};
+use tokio::time::{Instant, sleep};
+
+use crate::{
+    error::{AnglerfishError, Result, is_transaction_not_found},
+    journal::JournalRecord,
+};
+
+use super::{
+    client::AnglerfishClient,
+    engine::{Operation, PhaseIterator, Transition},
+    sui_client::ExecutionResult,
+    types::phase_info::PhaseInfo,
+};
+
+/// Outcome of an executed operation, as observed on-chain after the
+/// transaction has been checkpointed.
+#[derive(Debug)]
+pub enum TransitionOutcome {
+    /// The phase moved as expected.
+    Confirmed {
+        result: ExecutionResult,
+        checkpoint: CheckpointSequenceNumber,
+        phase_info: PhaseInfo,
+    },
+    /// The transaction succeeded but the expected phase was not observed.
+    /// `checkpoint` is `None` if the transaction was not checkpointed in time,
+    /// in which case the phase was read anyway.
+    NotObserved {
+        result: ExecutionResult,
+        checkpoint: Option<CheckpointSequenceNumber>,
+        expected: Transition,
+        phase_info: PhaseInfo,
+    },
+}
+
+impl TransitionOutcome {
+    pub fn result(&self) -> &ExecutionResult {
+        match self {
+            TransitionOutcome::Confirmed { result, .. }
+            | TransitionOutcome::NotObserved { result, .. } => result,
+        }
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        matches!(self, TransitionOutcome::Confirmed { .. })
+    }
+}
+
+/// AnglerfishConfirmation trait
+/// This trait checks that an executed operation actually advanced the phase,
+/// since a successful transaction alone does not guarantee it.
+#[async_trait::async_trait]
+pub trait AnglerfishConfirmation {
+    /// Waits until the transaction is included in a checkpoint. Returns `None`
+    /// if it is not checkpointed within the configured timeout, or the error
+    /// of the last read if the node could not tell.
+    async fn wait_for_checkpoint(
+        &self,
+        digest: TransactionDigest,
+    ) -> Result<Option<CheckpointSequenceNumber>>;
+
+    /// Waits for the transaction of `result` to be checkpointed, then re-reads
+    /// the phase and compares it with the transition expected from `before`.
+    async fn confirm_transition(
+        &self,
+        before: &PhaseInfo,
+        operation: Operation,
+        result: ExecutionResult,
+    ) -> Result<TransitionOutcome>;
+}
+
+#[async_trait::async_trait]
+impl AnglerfishConfirmation for AnglerfishClient {
+    async fn wait_for_checkpoint(
+        &self,
+        digest: TransactionDigest,
+    ) -> Result<Option<CheckpointSequenceNumber>> {
+        let confirmation = &self.config().confirmation;
+        let deadline = Instant::now() + Duration::from_secs(confirmation.timeout_secs);
+        let poll_interval = Duration::from_millis(confirmation.poll_interval_ms);
+
+        loop {
+            // the full node may not know the transaction yet, only its answer
+            // that the transaction is not found means it was not executed, a
+            // failed request is retried but never taken for that answer
+            let last_error = match self
+                .sui_client()
+                .read_api()
+                .get_transaction_with_options(digest, SuiTransactionBlockResponseOptions::new())
+                .await
+            {
+                Ok(response) if response.checkpoint.is_some() => {
+                    return Ok(response.checkpoint);
+                }
+                Ok(_) => None,
+                Err(e) if is_transaction_not_found(&e) => None,
+                Err(e) => Some(e),
+            };
+            if Instant::now() + poll_interval > deadline {
+                return match last_error {
+                    Some(e) => Err(e.into()),
+                    None => Ok(None),
+                };
+            }
+            sleep(poll_interval).await;
+        }
+    }
+
+    async fn confirm_transition(
+        &self,
+        before: &PhaseInfo,
+        operation: Operation,
+        result: ExecutionResult,
+    ) -> Result<TransitionOutcome> {
+        let expected = PhaseIterator::new()
+            .expected_transition(before, operation)
+            .ok_or(AnglerfishError::OperationNotApplicable {
+                operation,
+                phase: before.current_phase,
+            })?;
+
+        let checkpoint = self.wait_for_checkpoint(result.digest).await?;
+        let phase_info = self.get_phase_info().await?;
+
+        let transition_observed = checkpoint.is_some() && expected.is_observed_in(&phase_info);
+        self.record(
+            operation,
+            before,
+            JournalRecord::Confirmed {
+                digest: result.digest,
+                checkpoint,
+                transition_observed,
+            },
+        )?;
+
+        Ok(match checkpoint {
+            Some(checkpoint) if transition_observed => TransitionOutcome::Confirmed {
+                result,
+                checkpoint,
+                phase_info,
+            },
+            _ => TransitionOutcome::NotObserved {
+                result,
+                checkpoint,
+                expected,
+                phase_info,
+            },
+        })
+    }
+}
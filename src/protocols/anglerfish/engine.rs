@@ -0,0 +1,282 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::types::phase_info::{Phase, PhaseInfo};
+
+/// Iterator capability calls that advance the Anglerfish protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    NextEntry,
+    Draw,
+    Distribute,
+    StartNewRound,
+}
+
+impl Operation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::NextEntry => "next_entry",
+            Operation::Draw => "draw",
+            Operation::Distribute => "distribute",
+            Operation::StartNewRound => "start_new_round",
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The next thing the iterator should do for a given `PhaseInfo`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// The protocol has not been started yet, nothing can be done.
+    Idle,
+    /// The current phase cannot be advanced before the given timestamp (ms).
+    WaitUntil(u64),
+    /// The current phase can be advanced by submitting the operation.
+    Execute(Operation),
+}
+
+/// Phase and round number the protocol should be in once an operation has
+/// been executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    pub phase: Phase,
+    pub round_number: u64,
+}
+
+impl Transition {
+    /// Returns true if `phase_info` reflects this transition.
+    pub fn is_observed_in(&self, phase_info: &PhaseInfo) -> bool {
+        phase_info.current_phase == self.phase
+            && phase_info.current_round_number == self.round_number
+    }
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} (round {})", self.phase, self.round_number)
+    }
+}
+
+/// Phase state machine of the Anglerfish protocol.
+///
+/// `PhaseIterator` maps the on-chain `PhaseInfo` and the current time to the
+/// next `Action`. It does not perform any I/O, the caller is responsible for
+/// fetching the phase and submitting the resulting operation.
+#[derive(Debug, Clone, Default)]
+pub struct PhaseIterator;
+
+impl PhaseIterator {
+    pub fn new() -> Self {
+        PhaseIterator
+    }
+
+    pub fn next_action(&self, phase_info: &PhaseInfo, now_ms: u64) -> Action {
+        match phase_info.current_phase {
+            Phase::Uninitialized => Action::Idle,
+            Phase::LiquidityProviding | Phase::Ticketing => {
+                match phase_info.current_phase_deadline() {
+                    Some(deadline) if now_ms <= deadline => Action::WaitUntil(deadline),
+                    _ => Action::Execute(Operation::NextEntry),
+                }
+            }
+            Phase::Drawing => Action::Execute(Operation::Draw),
+            Phase::Distributing => Action::Execute(Operation::Distribute),
+            Phase::Settling => Action::Execute(Operation::StartNewRound),
+        }
+    }
+
+    /// Returns the state the protocol moves to when `operation` is executed
+    /// from `phase_info`, or `None` if the operation does not apply to the
+    /// current phase.
+    pub fn expected_transition(
+        &self,
+        phase_info: &PhaseInfo,
+        operation: Operation,
+    ) -> Option<Transition> {
+        let round_number = phase_info.current_round_number;
+        let (phase, round_number) = match (phase_info.current_phase, operation) {
+            (Phase::LiquidityProviding, Operation::NextEntry) => (Phase::Ticketing, round_number),
+            (Phase::Ticketing, Operation::NextEntry) => (Phase::Drawing, round_number),
+            (Phase::Drawing, Operation::Draw) => (Phase::Distributing, round_number),
+            (Phase::Distributing, Operation::Distribute) => (Phase::Settling, round_number),
+            (Phase::Settling, Operation::StartNewRound) => {
+                (Phase::LiquidityProviding, round_number + 1)
+            }
+            _ => return None,
+        };
+        Some(Transition {
+            phase,
+            round_number,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_sdk::types::{base_types::ObjectID, id::UID};
+
+    use super::*;
+    use crate::protocols::anglerfish::types::phase_info::PhaseDurations;
+
+    const ROUND: u64 = 7;
+    const PHASE_AT: u64 = 1_000_000;
+    const LP_DURATION: u64 = 60_000;
+    const TICKETING_DURATION: u64 = 120_000;
+
+    fn phase_info(current_phase: Phase) -> PhaseInfo {
+        PhaseInfo {
+            id: UID::new(ObjectID::ZERO),
+            current_round_number: ROUND,
+            current_phase,
+            current_phase_at: PHASE_AT,
+            durations: PhaseDurations {
+                liquidity_providing_duration: LP_DURATION,
+                ticketing_duration: TICKETING_DURATION,
+            },
+            last_drawing_timestamp_ms: 0,
+        }
+    }
+
+    #[test]
+    fn next_action_for_every_phase() {
+        let lp_deadline = PHASE_AT + LP_DURATION;
+        let ticketing_deadline = PHASE_AT + TICKETING_DURATION;
+        let cases = [
+            // (phase, now_ms, expected action)
+            (Phase::Uninitialized, PHASE_AT, Action::Idle),
+            (Phase::Uninitialized, u64::MAX, Action::Idle),
+            (
+                Phase::LiquidityProviding,
+                PHASE_AT,
+                Action::WaitUntil(lp_deadline),
+            ),
+            // Within a few seconds of the deadline, the scheduler lead time.
+            (
+                Phase::LiquidityProviding,
+                lp_deadline - 5_000,
+                Action::WaitUntil(lp_deadline),
+            ),
+            // The deadline itself is not past yet.
+            (
+                Phase::LiquidityProviding,
+                lp_deadline,
+                Action::WaitUntil(lp_deadline),
+            ),
+            (
+                Phase::LiquidityProviding,
+                lp_deadline + 1,
+                Action::Execute(Operation::NextEntry),
+            ),
+            (
+                Phase::Ticketing,
+                ticketing_deadline - 1,
+                Action::WaitUntil(ticketing_deadline),
+            ),
+            (
+                Phase::Ticketing,
+                ticketing_deadline,
+                Action::WaitUntil(ticketing_deadline),
+            ),
+            (
+                Phase::Ticketing,
+                ticketing_deadline + 1,
+                Action::Execute(Operation::NextEntry),
+            ),
+            (Phase::Drawing, PHASE_AT, Action::Execute(Operation::Draw)),
+            (
+                Phase::Distributing,
+                PHASE_AT,
+                Action::Execute(Operation::Distribute),
+            ),
+            (
+                Phase::Settling,
+                PHASE_AT,
+                Action::Execute(Operation::StartNewRound),
+            ),
+        ];
+        for (phase, now_ms, expected) in cases {
+            assert_eq!(
+                PhaseIterator::new().next_action(&phase_info(phase), now_ms),
+                expected,
+                "{:?} at {}",
+                phase,
+                now_ms
+            );
+        }
+    }
+
+    #[test]
+    fn expected_transition_for_every_phase() {
+        let transition = |phase, round_number| {
+            Some(Transition {
+                phase,
+                round_number,
+            })
+        };
+        let cases = [
+            // (phase, operation, expected transition)
+            (
+                Phase::LiquidityProviding,
+                Operation::NextEntry,
+                transition(Phase::Ticketing, ROUND),
+            ),
+            (
+                Phase::Ticketing,
+                Operation::NextEntry,
+                transition(Phase::Drawing, ROUND),
+            ),
+            (
+                Phase::Drawing,
+                Operation::Draw,
+                transition(Phase::Distributing, ROUND),
+            ),
+            (
+                Phase::Distributing,
+                Operation::Distribute,
+                transition(Phase::Settling, ROUND),
+            ),
+            (
+                Phase::Settling,
+                Operation::StartNewRound,
+                transition(Phase::LiquidityProviding, ROUND + 1),
+            ),
+            (Phase::Uninitialized, Operation::NextEntry, None),
+            (Phase::Uninitialized, Operation::StartNewRound, None),
+            (Phase::LiquidityProviding, Operation::Draw, None),
+            (Phase::Ticketing, Operation::Distribute, None),
+            (Phase::Drawing, Operation::NextEntry, None),
+            (Phase::Distributing, Operation::StartNewRound, None),
+            (Phase::Settling, Operation::Draw, None),
+        ];
+        for (phase, operation, expected) in cases {
+            assert_eq!(
+                PhaseIterator::new().expected_transition(&phase_info(phase), operation),
+                expected,
+                "{} from {:?}",
+                operation,
+                phase
+            );
+        }
+    }
+
+    #[test]
+    fn transition_is_observed_in_phase_info() {
+        let transition = Transition {
+            phase: Phase::Ticketing,
+            round_number: ROUND,
+        };
+        assert!(transition.is_observed_in(&phase_info(Phase::Ticketing)));
+        assert!(!transition.is_observed_in(&phase_info(Phase::LiquidityProviding)));
+
+        let mut next_round = phase_info(Phase::Ticketing);
+        next_round.current_round_number = ROUND + 1;
+        assert!(!transition.is_observed_in(&next_round));
+    }
+}
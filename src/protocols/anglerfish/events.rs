@@ -0,0 +1,131 @@
+use futures::{Stream, TryStreamExt, stream};
+use serde::Serialize;
+use sui_sdk::{
+    rpc_types::EventFilter,
+    types::{Identifier, event::EventID, parse_sui_struct_tag},
+};
+
+use crate::error::{AnglerfishError, Result};
+
+use super::{
+    client::AnglerfishClient,
+    types::events::{AnglerfishEvent, AnglerfishEventType},
+};
+
+/// Events to query, always scoped to the configured `original_package_id`,
+/// which event types keep across package upgrades.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnglerfishEventFilter {
+    /// Events of the `phase` module.
+    Phase,
+    /// Events of the `prize_pool` module.
+    PrizePool,
+    /// Events of a single type.
+    Type(AnglerfishEventType),
+}
+
+/// Decoded event, with the transaction which emitted it.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmittedEvent {
+    pub id: EventID,
+    pub timestamp_ms: Option<u64>,
+    pub event: AnglerfishEvent,
+}
+
+/// AnglerfishEvents trait
+/// This trait reads the events emitted by the Anglerfish package, decoded into
+/// their Rust representation.
+pub trait AnglerfishEvents {
+    /// Streams the events matching `filter`, oldest first. Events of other
+    /// types emitted by the same modules are skipped.
+    ///
+    /// An event which cannot be decoded is yielded as an error and the stream
+    /// goes on, so the caller decides whether to skip it. A failed RPC request
+    /// ends the stream.
+    fn query_events(
+        &self,
+        filter: AnglerfishEventFilter,
+    ) -> impl Stream<Item = Result<EmittedEvent>> + '_;
+}
+
+/// Position in the event pages.
+struct Cursor {
+    cursor: Option<EventID>,
+    has_next_page: bool,
+}
+
+impl AnglerfishEvents for AnglerfishClient {
+    fn query_events(
+        &self,
+        filter: AnglerfishEventFilter,
+    ) -> impl Stream<Item = Result<EmittedEvent>> + '_ {
+        let start = Cursor {
+            cursor: None,
+            has_next_page: true,
+        };
+
+        stream::try_unfold(start, move |cursor| async move {
+            if !cursor.has_next_page {
+                return Ok(None);
+            }
+            let page = self
+                .observe_rpc(
+                    "query_events",
+                    self.sui_client().event_api().query_events(
+                        event_filter(self, filter)?,
+                        cursor.cursor,
+                        None,
+                        false,
+                    ),
+                )
+                .await?;
+
+            let mut events = Vec::with_capacity(page.data.len());
+            for event in &page.data {
+                match AnglerfishEvent::try_from_sui_event(event, self.original_package_id()) {
+                    Ok(Some(decoded)) => events.push(Ok(EmittedEvent {
+                        id: event.id,
+                        timestamp_ms: event.timestamp_ms,
+                        event: decoded,
+                    })),
+                    Ok(None) => {}
+                    Err(e) => events.push(Err(e)),
+                }
+            }
+
+            let next = Cursor {
+                cursor: page.next_cursor,
+                has_next_page: page.has_next_page && page.next_cursor.is_some(),
+            };
+            Ok(Some((stream::iter(events), next)))
+        })
+        .try_flatten()
+    }
+}
+
+fn event_filter(client: &AnglerfishClient, filter: AnglerfishEventFilter) -> Result<EventFilter> {
+    let package = client.original_package_id();
+    let module_filter = |module: &str| {
+        Identifier::new(module)
+            .map(|module| EventFilter::MoveEventModule { package, module })
+            .map_err(|e| AnglerfishError::Config(format!("Invalid module {}: {}", module, e)))
+    };
+
+    match filter {
+        AnglerfishEventFilter::Phase => module_filter("phase"),
+        AnglerfishEventFilter::PrizePool => module_filter("prize_pool"),
+        AnglerfishEventFilter::Type(event_type) => {
+            let struct_tag = format!(
+                "{}::{}::{}",
+                package,
+                event_type.module(),
+                event_type.name()
+            );
+            parse_sui_struct_tag(&struct_tag)
+                .map(EventFilter::MoveEventType)
+                .map_err(|e| {
+                    AnglerfishError::Config(format!("Invalid event type {}: {}", struct_tag, e))
+                })
+        }
+    }
+}
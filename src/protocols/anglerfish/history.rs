@@ -0,0 +1,154 @@
+use std::ops::{Bound, RangeBounds};
+
+use futures::{Stream, TryStreamExt, stream};
+use serde_json::Value;
+use sui_sdk::{
+    rpc_types::{DynamicFieldInfo, SuiObjectDataOptions},
+    types::base_types::ObjectID,
+};
+
+use crate::{
+    error::{AnglerfishError, Result},
+    helper::sui::SuiObjectBuilder,
+};
+
+use super::{
+    client::AnglerfishClient,
+    types::{
+        FieldsExtractor,
+        round::{Round, RoundEntry},
+    },
+};
+
+/// AnglerfishRoundHistory trait
+/// This trait enumerates the past and current rounds by paging through the
+/// `RoundRegistry.rounds` table.
+pub trait AnglerfishRoundHistory {
+    /// Streams the rounds whose number is within `range`, as
+    /// `(round_number, round)`.
+    ///
+    /// The rounds come in the order of the table, which is not the round
+    /// number order. Each page of the table costs three RPC requests.
+    fn get_rounds(
+        &self,
+        range: impl RangeBounds<u64>,
+    ) -> impl Stream<Item = Result<(u64, Round)>> + '_;
+}
+
+/// Position in the `RoundRegistry.rounds` table.
+struct Cursor {
+    /// Resolved on the first page.
+    table_id: Option<ObjectID>,
+    cursor: Option<ObjectID>,
+    has_next_page: bool,
+}
+
+impl AnglerfishRoundHistory for AnglerfishClient {
+    fn get_rounds(
+        &self,
+        range: impl RangeBounds<u64>,
+    ) -> impl Stream<Item = Result<(u64, Round)>> + '_ {
+        let range: (Bound<u64>, Bound<u64>) =
+            (range.start_bound().cloned(), range.end_bound().cloned());
+        let start = Cursor {
+            table_id: None,
+            cursor: None,
+            has_next_page: true,
+        };
+
+        stream::try_unfold(start, move |cursor| async move {
+            if !cursor.has_next_page {
+                return Ok(None);
+            }
+            let table_id = match cursor.table_id {
+                Some(table_id) => table_id,
+                None => self.get_round_registry().await?.rounds.id,
+            };
+
+            let page = self
+                .observe_rpc(
+                    "get_dynamic_fields",
+                    self.sui_client()
+                        .read_api()
+                        .get_dynamic_fields(table_id, cursor.cursor, None),
+                )
+                .await?;
+            let fields = page
+                .data
+                .iter()
+                .filter(|field| round_number(field).is_some_and(|n| range.contains(&n)))
+                .map(|field| field.object_id)
+                .collect::<Vec<_>>();
+            let rounds = fetch_rounds(self, fields).await?;
+
+            let next = Cursor {
+                table_id: Some(table_id),
+                cursor: page.next_cursor,
+                has_next_page: page.has_next_page && page.next_cursor.is_some(),
+            };
+            Ok(Some((stream::iter(rounds.into_iter().map(Ok)), next)))
+        })
+        .try_flatten()
+    }
+}
+
+/// Round number of a `RoundRegistry.rounds` entry, `u64` keys are listed as
+/// JSON strings.
+fn round_number(field: &DynamicFieldInfo) -> Option<u64> {
+    match &field.name.value {
+        Value::String(value) => value.parse().ok(),
+        Value::Number(value) => value.as_u64(),
+        _ => None,
+    }
+}
+
+/// Fetches the entries `fields` of the table, then the rounds they point to.
+async fn fetch_rounds(
+    client: &AnglerfishClient,
+    fields: Vec<ObjectID>,
+) -> Result<Vec<(u64, Round)>> {
+    if fields.is_empty() {
+        return Ok(vec![]);
+    }
+    let read_api = client.sui_client().read_api();
+
+    let entries = client
+        .observe_rpc(
+            "multi_get_objects",
+            read_api.multi_get_object_with_options(
+                fields.clone(),
+                SuiObjectDataOptions::default().with_content(),
+            ),
+        )
+        .await?
+        .into_iter()
+        .zip(fields)
+        .map(|(response, field_id)| {
+            let data = response
+                .data
+                .ok_or(AnglerfishError::ObjectNotFound(field_id))?;
+            RoundEntry::try_from_sui_obj_data(data)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let round_ids = entries.iter().map(|entry| entry.round_id).collect();
+    let responses = client
+        .observe_rpc(
+            "multi_get_objects",
+            read_api.multi_get_object_with_options(
+                round_ids,
+                SuiObjectDataOptions::default().with_bcs(),
+            ),
+        )
+        .await?;
+
+    let mut rounds = Vec::with_capacity(entries.len());
+    for (response, entry) in responses.into_iter().zip(entries) {
+        let data = response
+            .data
+            .ok_or(AnglerfishError::ObjectNotFound(entry.round_id))?;
+        let round = client.sui_client().parse_obj_bcs::<Round>(data).await?;
+        rounds.push((entry.round_number, round));
+    }
+    Ok(rounds)
+}
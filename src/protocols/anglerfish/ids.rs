@@ -1,8 +1,23 @@
+//! Built-in ids of the Anglerfish testnet deployment.
+//!
+//! They are only used by the `testnet` configuration preset, the iterator
+//! always reads object ids from the loaded `Config`.
+
 use std::str::FromStr;
 
 use sui_sdk::types::base_types::ObjectID;
 
-///
+use crate::config::Objects;
+
+pub fn testnet_objects() -> Objects {
+    Objects {
+        phase_info_id: anglerfish_phase_info_obj_id(),
+        round_registry_id: anglerfish_round_registry_obj_id(),
+        pool_registry_id: anglerfish_pool_registry_obj_id(),
+        prize_pool_id: anglerfish_prize_pool_obj_id(),
+        lounge_registry_id: anglerfish_lounge_registry_obj_id(),
+    }
+}
 
 pub fn anglerfish_package_obj_id() -> ObjectID {
     ObjectID::from_str(&anglerfish_package_id()).unwrap()
@@ -53,3 +68,7 @@ pub fn anglerfish_prize_pool_id() -> String {
 pub fn anglerfish_lounge_registry_id() -> String {
     String::from("0x7ed2df7a7ee900f4deec82211464babf2dc568185d332371bcabe63def09b7a5")
 }
+
+pub fn anglerfish_pool_coin_type() -> String {
+    String::from("0xc51004215439bd6a6acd47e5fd128b264203cba1059afd5374341e5d850326fc::usdc::USDC")
+}
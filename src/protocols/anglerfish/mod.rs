@@ -1,5 +1,13 @@
 pub mod client;
+pub mod confirmation;
+pub mod engine;
+pub mod events;
+pub mod history;
 pub mod ids;
 pub mod ptb;
+pub mod purchases;
+pub mod retry;
+pub mod scheduler;
 pub mod sui_client;
 pub mod types;
+pub mod validator;
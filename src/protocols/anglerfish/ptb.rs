@@ -1,12 +1,15 @@
-use anyhow::Result;
 use sui_sdk::types::{
+    base_types::ObjectID,
     programmable_transaction_builder::ProgrammableTransactionBuilder,
-    transaction::{Command, ProgrammableMoveCall},
+    transaction::{Argument, Command, ObjectArg, ProgrammableMoveCall},
 };
 
-use crate::helper::{sui::SuiObjectBuilder, type_input::ToTypeInputs};
+use crate::{
+    error::{AnglerfishError, Result},
+    helper::{sui::SuiObjectBuilder, type_input::ToTypeInputs},
+};
 
-use super::{client::AnglerfishClient, ids, types::round::Round};
+use super::client::AnglerfishClient;
 
 /// AnglerfishProgramableTransaction trait
 /// This trait defines the programmable transaction of iterator capability methods for the Anglerfish protocol.
@@ -18,12 +21,12 @@ pub trait AnglerfishProgramableTransaction {
     async fn build_draw(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
-        round: &Round,
+        round_id: ObjectID,
     ) -> Result<()>;
     async fn build_distribute(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
-        round: &Round,
+        round_id: ObjectID,
     ) -> Result<()>;
 }
 
@@ -31,20 +34,17 @@ pub trait AnglerfishProgramableTransaction {
 impl AnglerfishProgramableTransaction for AnglerfishClient {
     async fn build_next_entry(&self, ptb: &mut ProgrammableTransactionBuilder) -> Result<()> {
         let sui_client = self.sui_client();
+        let objects = self.objects();
         let iter_cap_id = self.iterator_cap_id();
 
-        let iter_cap = ptb.obj(sui_client.owned_obj(&iter_cap_id).await?)?;
+        let iter_cap = ptb.input(sui_client.owned_obj(iter_cap_id).await?)?;
 
-        let phase_info = ptb.obj(
-            sui_client
-                .shared_obj_mut(&ids::anglerfish_phase_info_id())
-                .await?,
-        )?;
+        let phase_info = ptb.input(sui_client.shared_obj_mut(objects.phase_info_id).await?)?;
 
-        let clock = ptb.obj(sui_client.clock().await?)?;
+        let clock = ptb.input(sui_client.clock().await?)?;
 
         ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
-            package: ids::anglerfish_package_obj_id(),
+            package: self.package_id(),
             module: "phase".to_string(),
             function: "next_entry".to_string(),
             type_arguments: vec![],
@@ -56,28 +56,18 @@ impl AnglerfishProgramableTransaction for AnglerfishClient {
 
     async fn build_start_new_round(&self, ptb: &mut ProgrammableTransactionBuilder) -> Result<()> {
         let sui_client = self.sui_client();
+        let objects = self.objects();
         let iter_cap_id = self.iterator_cap_id();
 
-        let iter_cap = ptb.obj(sui_client.owned_obj(&iter_cap_id).await?)?;
-        let phase_info = ptb.obj(
-            sui_client
-                .shared_obj_mut(&ids::anglerfish_phase_info_id())
-                .await?,
-        )?;
-        let round_registry = ptb.obj(
-            sui_client
-                .shared_obj_mut(&ids::anglerfish_round_registry_id())
-                .await?,
-        )?;
-        let prize_pool = ptb.obj(
-            sui_client
-                .shared_obj(&ids::anglerfish_prize_pool_id())
-                .await?,
-        )?;
-        let clock = ptb.obj(sui_client.clock().await?)?;
+        let iter_cap = ptb.input(sui_client.owned_obj(iter_cap_id).await?)?;
+        let phase_info = ptb.input(sui_client.shared_obj_mut(objects.phase_info_id).await?)?;
+        let round_registry =
+            ptb.input(sui_client.shared_obj_mut(objects.round_registry_id).await?)?;
+        let prize_pool = ptb.input(sui_client.shared_obj(objects.prize_pool_id).await?)?;
+        let clock = ptb.input(sui_client.clock().await?)?;
 
         ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
-            package: ids::anglerfish_package_obj_id(),
+            package: self.package_id(),
             module: "prize_pool".to_string(),
             function: "start_new_round".to_string(),
             type_arguments: vec![],
@@ -89,43 +79,24 @@ impl AnglerfishProgramableTransaction for AnglerfishClient {
     async fn build_draw(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
-        round: &Round,
+        round_id: ObjectID,
     ) -> Result<()> {
         let sui_client = self.sui_client();
+        let objects = self.objects();
         let iter_cap_id = self.iterator_cap_id();
         let pool_coin_type = self.pool_coin_type();
 
-        let iter_cap = ptb.obj(sui_client.owned_obj(&iter_cap_id).await?)?;
-        let prize_pool = ptb.obj(
-            sui_client
-                .shared_obj(&ids::anglerfish_prize_pool_id())
-                .await?,
-        )?;
-        let phase_info = ptb.obj(
-            sui_client
-                .shared_obj_mut(&ids::anglerfish_phase_info_id())
-                .await?,
-        )?;
-        let pool_registry = ptb.obj(
-            sui_client
-                .shared_obj(&ids::anglerfish_pool_registry_id())
-                .await?,
-        )?;
-        let round_registry = ptb.obj(
-            sui_client
-                .shared_obj(&ids::anglerfish_round_registry_id())
-                .await?,
-        )?;
-        let round = ptb.obj(
-            sui_client
-                .shared_obj_mut(&round.id.id.bytes.to_string())
-                .await?,
-        )?;
-        let randomness = ptb.obj(sui_client.randomness().await?)?;
-        let clock = ptb.obj(sui_client.clock().await?)?;
+        let iter_cap = ptb.input(sui_client.owned_obj(iter_cap_id).await?)?;
+        let prize_pool = ptb.input(sui_client.shared_obj(objects.prize_pool_id).await?)?;
+        let phase_info = ptb.input(sui_client.shared_obj_mut(objects.phase_info_id).await?)?;
+        let pool_registry = ptb.input(sui_client.shared_obj(objects.pool_registry_id).await?)?;
+        let round_registry = ptb.input(sui_client.shared_obj(objects.round_registry_id).await?)?;
+        let round = ptb.input(sui_client.shared_obj_mut(round_id).await?)?;
+        let randomness = ptb.input(sui_client.randomness().await?)?;
+        let clock = ptb.input(sui_client.clock().await?)?;
 
         ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
-            package: ids::anglerfish_package_obj_id(),
+            package: self.package_id(),
             module: "prize_pool".to_string(),
             function: "draw".to_string(),
             type_arguments: vec![pool_coin_type].to_type_inputs(),
@@ -146,47 +117,29 @@ impl AnglerfishProgramableTransaction for AnglerfishClient {
     async fn build_distribute(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
-        round: &Round,
+        round_id: ObjectID,
     ) -> Result<()> {
         let sui_client = self.sui_client();
+        let objects = self.objects();
         let iter_cap_id = self.iterator_cap_id();
         let pool_coin_type = self.pool_coin_type();
 
-        let iter_cap = ptb.obj(sui_client.owned_obj(&iter_cap_id).await?)?;
-        let phase_info = ptb.obj(
-            sui_client
-                .shared_obj_mut(&ids::anglerfish_phase_info_id())
-                .await?,
-        )?;
-        let prize_pool = ptb.obj(
-            sui_client
-                .shared_obj_mut(&ids::anglerfish_prize_pool_id())
-                .await?,
-        )?;
-        let pool_registry = ptb.obj(
-            sui_client
-                .shared_obj_mut(&ids::anglerfish_pool_registry_id())
-                .await?,
-        )?;
-        let lounge_registry = ptb.obj(
-            sui_client
-                .shared_obj_mut(&ids::anglerfish_lounge_registry_id())
-                .await?,
-        )?;
-        let round_registry = ptb.obj(
-            sui_client
-                .shared_obj(&ids::anglerfish_round_registry_id())
-                .await?,
-        )?;
-        let round = ptb.obj(
+        let iter_cap = ptb.input(sui_client.owned_obj(iter_cap_id).await?)?;
+        let phase_info = ptb.input(sui_client.shared_obj_mut(objects.phase_info_id).await?)?;
+        let prize_pool = ptb.input(sui_client.shared_obj_mut(objects.prize_pool_id).await?)?;
+        let pool_registry =
+            ptb.input(sui_client.shared_obj_mut(objects.pool_registry_id).await?)?;
+        let lounge_registry = ptb.input(
             sui_client
-                .shared_obj_mut(&round.id.id.bytes.to_string())
+                .shared_obj_mut(objects.lounge_registry_id)
                 .await?,
         )?;
-        let clock = ptb.obj(sui_client.clock().await?)?;
+        let round_registry = ptb.input(sui_client.shared_obj(objects.round_registry_id).await?)?;
+        let round = ptb.input(sui_client.shared_obj_mut(round_id).await?)?;
+        let clock = ptb.input(sui_client.clock().await?)?;
 
         ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
-            package: ids::anglerfish_package_obj_id(),
+            package: self.package_id(),
             module: "prize_pool".to_string(),
             function: "distribute".to_string(),
             type_arguments: vec![pool_coin_type].to_type_inputs(),
@@ -204,3 +157,15 @@ impl AnglerfishProgramableTransaction for AnglerfishClient {
         Ok(())
     }
 }
+
+/// Object inputs of the programmable transaction builder, with typed errors.
+trait ObjectInput {
+    fn input(&mut self, obj_arg: ObjectArg) -> Result<Argument>;
+}
+
+impl ObjectInput for ProgrammableTransactionBuilder {
+    fn input(&mut self, obj_arg: ObjectArg) -> Result<Argument> {
+        self.obj(obj_arg)
+            .map_err(|e| AnglerfishError::TransactionBuild(e.to_string()))
+    }
+}
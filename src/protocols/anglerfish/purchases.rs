@@ -0,0 +1,326 @@
+use std::{future::Future, ops::Range};
+
+use sui_sdk::{
+    rpc_types::{Page, SuiObjectDataOptions},
+    types::{
+        TypeTag,
+        base_types::{ObjectID, SuiAddress},
+        dynamic_field::{Field, derive_dynamic_field_id},
+    },
+};
+
+use crate::{
+    error::{AnglerfishError, Result},
+    helper::sui::SuiObjectBuilder,
+};
+
+use super::{
+    client::AnglerfishClient,
+    types::round::{Purchase, Round},
+};
+
+/// Maximum number of objects of a `multi_get_objects` request.
+const PURCHASES_PAGE_LIMIT: u64 = 50;
+
+/// AnglerfishPurchases trait
+/// This trait reads the purchases of a round, which are stored in order in the
+/// `Round.purchases` table vector, and the tickets they own.
+#[async_trait::async_trait]
+pub trait AnglerfishPurchases {
+    /// Returns up to `limit` purchases of `round` starting at the purchase
+    /// `cursor` (0 if `None`), the next cursor is the index of the next purchase.
+    async fn get_purchases(
+        &self,
+        round: &Round,
+        cursor: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<Purchase, u64>>;
+
+    /// Returns every purchase of `round`, in buying order.
+    async fn get_all_purchases(&self, round: &Round) -> Result<Vec<Purchase>>;
+
+    /// Returns the ranges of ticket indexes owned by `address` in `round`.
+    async fn get_tickets_of(&self, round: &Round, address: SuiAddress) -> Result<Vec<Range<u64>>>;
+
+    /// Returns the owner of the ticket `ticket_index` of `round`, or `None` if
+    /// the ticket was not sold.
+    async fn get_ticket_owner(
+        &self,
+        round: &Round,
+        ticket_index: u64,
+    ) -> Result<Option<SuiAddress>>;
+}
+
+#[async_trait::async_trait]
+impl AnglerfishPurchases for AnglerfishClient {
+    async fn get_purchases(
+        &self,
+        round: &Round,
+        cursor: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<Purchase, u64>> {
+        let purchase_count = round.purchases.contents.size;
+        let indexes = page_indexes(purchase_count, cursor, limit);
+        let end = indexes.end;
+
+        let purchases = fetch_purchases(self, round, indexes).await?;
+        let has_next_page = end < purchase_count;
+        Ok(Page {
+            data: purchases,
+            next_cursor: has_next_page.then_some(end),
+            has_next_page,
+        })
+    }
+
+    async fn get_all_purchases(&self, round: &Round) -> Result<Vec<Purchase>> {
+        let mut purchases = Vec::with_capacity(round.purchases.contents.size as usize);
+        let mut cursor = None;
+        loop {
+            let page = self.get_purchases(round, cursor, None).await?;
+            purchases.extend(page.data);
+            match page.next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => return Ok(purchases),
+            }
+        }
+    }
+
+    async fn get_tickets_of(&self, round: &Round, address: SuiAddress) -> Result<Vec<Range<u64>>> {
+        let tickets = self
+            .get_all_purchases(round)
+            .await?
+            .iter()
+            .filter(|purchase| purchase.address == address)
+            .map(Purchase::tickets)
+            .collect();
+        Ok(tickets)
+    }
+
+    async fn get_ticket_owner(
+        &self,
+        round: &Round,
+        ticket_index: u64,
+    ) -> Result<Option<SuiAddress>> {
+        find_ticket_owner(
+            round.purchases.contents.size,
+            round.total_tickets,
+            ticket_index,
+            |index| async move {
+                fetch_purchases(self, round, index..index + 1)
+                    .await?
+                    .pop()
+                    .ok_or_else(|| {
+                        AnglerfishError::ObjectDecode(format!("Missing purchase {}", index))
+                    })
+            },
+        )
+        .await
+    }
+}
+
+/// Indexes of the purchases of the page starting at `cursor`, with at most
+/// `limit` purchases out of `purchase_count`.
+fn page_indexes(purchase_count: u64, cursor: Option<u64>, limit: Option<u64>) -> Range<u64> {
+    let limit = limit
+        .unwrap_or(PURCHASES_PAGE_LIMIT)
+        .clamp(1, PURCHASES_PAGE_LIMIT);
+    let start = cursor.unwrap_or(0).min(purchase_count);
+    let end = start.saturating_add(limit).min(purchase_count);
+    start..end
+}
+
+/// Binary search of the owner of `ticket_index` among `purchase_count`
+/// purchases, `fetch` returning the purchase of an index.
+async fn find_ticket_owner<F, Fut>(
+    purchase_count: u64,
+    total_tickets: u64,
+    ticket_index: u64,
+    mut fetch: F,
+) -> Result<Option<SuiAddress>>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<Purchase>>,
+{
+    if ticket_index >= total_tickets {
+        return Ok(None);
+    }
+
+    // The purchases are sorted by start index, look for the last one
+    // starting at or before the ticket.
+    let (mut low, mut high) = (0, purchase_count);
+    let mut owner = None;
+    while low < high {
+        let middle = low + (high - low) / 2;
+        let purchase = fetch(middle).await?;
+        if purchase.start_index <= ticket_index {
+            owner = purchase
+                .tickets()
+                .contains(&ticket_index)
+                .then_some(purchase.address);
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    Ok(owner)
+}
+
+/// Fetches the purchases `indexes` of `round` in a single request, from the ids
+/// of their table vector entries.
+async fn fetch_purchases(
+    client: &AnglerfishClient,
+    round: &Round,
+    indexes: Range<u64>,
+) -> Result<Vec<Purchase>> {
+    if indexes.is_empty() {
+        return Ok(vec![]);
+    }
+    let table_id = round.purchases.contents.id;
+    let field_ids = indexes
+        .clone()
+        .map(|index| purchase_field_id(table_id, index))
+        .collect::<Result<Vec<_>>>()?;
+
+    let responses = client
+        .observe_rpc(
+            "multi_get_objects",
+            client
+                .sui_client()
+                .read_api()
+                .multi_get_object_with_options(
+                    field_ids.clone(),
+                    SuiObjectDataOptions::default().with_bcs(),
+                ),
+        )
+        .await?;
+
+    let mut purchases = Vec::with_capacity(field_ids.len());
+    for (response, field_id) in responses.into_iter().zip(field_ids) {
+        let data = response
+            .data
+            .ok_or(AnglerfishError::ObjectNotFound(field_id))?;
+        let field = client
+            .sui_client()
+            .parse_obj_bcs::<Field<u64, Purchase>>(data)
+            .await?;
+        purchases.push(field.value);
+    }
+    Ok(purchases)
+}
+
+/// Id of the table vector entry `index`, a dynamic field keyed by `u64`.
+fn purchase_field_id(table_id: ObjectID, index: u64) -> Result<ObjectID> {
+    let key_bytes = bcs::to_bytes(&index)
+        .map_err(|e| AnglerfishError::ObjectDecode(format!("Invalid purchase index: {}", e)))?;
+    derive_dynamic_field_id(table_id, &TypeTag::U64, &key_bytes).map_err(|e| {
+        AnglerfishError::ObjectDecode(format!("Failed to derive purchase {} id: {}", index, e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_indexes_within_the_purchases() {
+        let cases = [
+            // (purchase count, cursor, limit, expected indexes)
+            (120, None, None, 0..50),
+            (120, Some(50), None, 50..100),
+            (120, Some(100), None, 100..120),
+            (120, Some(120), None, 120..120),
+            (120, Some(500), None, 120..120),
+            (120, Some(10), Some(5), 10..15),
+            (120, None, Some(0), 0..1),
+            (120, None, Some(1_000), 0..50),
+            (120, Some(u64::MAX), Some(50), 120..120),
+            (0, None, None, 0..0),
+        ];
+        for (purchase_count, cursor, limit, expected) in cases {
+            assert_eq!(
+                page_indexes(purchase_count, cursor, limit),
+                expected,
+                "{} purchases from {:?} by {:?}",
+                purchase_count,
+                cursor,
+                limit
+            );
+        }
+    }
+
+    fn purchases(tickets: &[(u64, u64)]) -> Vec<Purchase> {
+        tickets
+            .iter()
+            .map(|&(start_index, ticket_count)| Purchase {
+                address: SuiAddress::random_for_testing_only(),
+                ticket_count,
+                start_index,
+            })
+            .collect()
+    }
+
+    async fn owner(purchases: &[Purchase], total_tickets: u64, ticket_index: u64) -> Option<usize> {
+        let owner = find_ticket_owner(
+            purchases.len() as u64,
+            total_tickets,
+            ticket_index,
+            |index| {
+                let purchase = purchases[index as usize].clone();
+                async move { Ok(purchase) }
+            },
+        )
+        .await
+        .unwrap()?;
+        purchases
+            .iter()
+            .position(|purchase| purchase.address == owner)
+    }
+
+    #[tokio::test]
+    async fn find_ticket_owner_of_each_ticket() {
+        let purchases = purchases(&[(0, 3), (3, 1), (4, 5)]);
+        let cases = [
+            // (ticket index, expected purchase)
+            (0, Some(0)),
+            (2, Some(0)),
+            (3, Some(1)),
+            (4, Some(2)),
+            // last ticket
+            (8, Some(2)),
+            (9, None),
+            (u64::MAX, None),
+        ];
+        for (ticket_index, expected) in cases {
+            assert_eq!(
+                owner(&purchases, 9, ticket_index).await,
+                expected,
+                "ticket {}",
+                ticket_index
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn find_ticket_owner_between_purchases() {
+        let purchases = purchases(&[(0, 2), (5, 2)]);
+        assert_eq!(owner(&purchases, 7, 1).await, Some(0));
+        assert_eq!(owner(&purchases, 7, 3).await, None);
+        assert_eq!(owner(&purchases, 7, 5).await, Some(1));
+        assert_eq!(owner(&[], 0, 0).await, None);
+    }
+
+    #[tokio::test]
+    async fn find_ticket_owner_fetches_log_purchases() {
+        let purchases = purchases(&(0..1_000).map(|i| (i, 1)).collect::<Vec<_>>());
+        let mut fetched = 0;
+        let owner = find_ticket_owner(1_000, 1_000, 777, |index| {
+            fetched += 1;
+            let purchase = purchases[index as usize].clone();
+            async move { Ok(purchase) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(owner, Some(purchases[777].address));
+        assert!(fetched <= 10, "{} fetches", fetched);
+    }
+}
@@ -0,0 +1,399 @@
+use std::{
+    collections::hash_map::RandomState, fmt, future::Future, hash::BuildHasher, mem, time::Duration,
+};
+
+use tokio::time::sleep;
+use tracing::warn;
+
+use crate::{
+    config::{self, Backoff},
+    error::{AnglerfishError, Result},
+    helper::gas::GasError,
+};
+
+/// Category of a failed fetch or transaction, which decides how it is retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// RPC or network failure, the same request may succeed as is.
+    Transient,
+    /// Shared object version conflict or congestion.
+    SharedObjectConflict,
+    /// Owned object locked by another transaction, possibly until the end of the epoch.
+    ObjectLocked,
+    /// Move abort, with the aborting module, function and abort code.
+    MoveAbort {
+        module: String,
+        function: Option<String>,
+        code: u64,
+    },
+    /// Not enough SUI to pay for gas.
+    GasShortage,
+    Other,
+}
+
+impl fmt::Display for ErrorClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorClass::Transient => f.write_str("transient RPC error"),
+            ErrorClass::SharedObjectConflict => f.write_str("shared object conflict"),
+            ErrorClass::ObjectLocked => f.write_str("object locked"),
+            ErrorClass::MoveAbort {
+                module,
+                function: Some(function),
+                code,
+            } => write!(
+                f,
+                "move abort in {}::{} with code {}",
+                module, function, code
+            ),
+            ErrorClass::MoveAbort { module, code, .. } => {
+                write!(f, "move abort in {} with code {}", module, code)
+            }
+            ErrorClass::GasShortage => f.write_str("gas shortage"),
+            ErrorClass::Other => f.write_str("error"),
+        }
+    }
+}
+
+impl ErrorClass {
+    pub fn classify(error: &AnglerfishError) -> ErrorClass {
+        match error {
+            AnglerfishError::MoveAbort {
+                module,
+                function,
+                code,
+                ..
+            } => ErrorClass::MoveAbort {
+                module: module.clone(),
+                function: function.clone(),
+                code: *code,
+            },
+            AnglerfishError::ObjectLocked(_) => ErrorClass::ObjectLocked,
+            AnglerfishError::SharedObjectConflict(_) => ErrorClass::SharedObjectConflict,
+            AnglerfishError::Gas(GasError::Rpc(_)) => ErrorClass::Transient,
+            // the per-operation budget cap is a configuration issue, topping
+            // up the signer does not help
+            AnglerfishError::Gas(GasError::BudgetExceeded { .. }) => ErrorClass::Other,
+            AnglerfishError::Gas(
+                GasError::InsufficientBalance { .. } | GasError::TooFragmented { .. },
+            ) => ErrorClass::GasShortage,
+            AnglerfishError::Rpc(_)
+            | AnglerfishError::SignerUnavailable(_)
+            | AnglerfishError::OutcomeUnknown { .. } => ErrorClass::Transient,
+            _ => ErrorClass::Other,
+        }
+    }
+
+    /// Classifies an error wrapping an `AnglerfishError`, e.g. with added context.
+    pub fn classify_any(error: &anyhow::Error) -> ErrorClass {
+        error
+            .chain()
+            .find_map(|e| e.downcast_ref::<AnglerfishError>())
+            .map(ErrorClass::classify)
+            .unwrap_or(ErrorClass::Other)
+    }
+}
+
+/// Backoff state of the iterator, one policy per `ErrorClass`.
+///
+/// Consecutive failures of the same class back off exponentially, a failure of
+/// another class or a success resets the backoff.
+pub struct RetryPolicy {
+    config: config::Retry,
+    last_failure: Option<(ErrorClass, u32)>,
+}
+
+impl RetryPolicy {
+    pub fn new(config: config::Retry) -> Self {
+        RetryPolicy {
+            config,
+            last_failure: None,
+        }
+    }
+
+    pub fn backoff(&self, class: &ErrorClass) -> &Backoff {
+        match class {
+            ErrorClass::Transient => &self.config.transient,
+            ErrorClass::SharedObjectConflict => &self.config.shared_object_conflict,
+            ErrorClass::ObjectLocked => &self.config.object_locked,
+            ErrorClass::MoveAbort { .. } => &self.config.move_abort,
+            ErrorClass::GasShortage => &self.config.gas_shortage,
+            ErrorClass::Other => &self.config.other,
+        }
+    }
+
+    /// Records a failure and returns how long to wait before the next attempt.
+    pub fn on_failure(&mut self, class: &ErrorClass) -> Duration {
+        let attempt = match &self.last_failure {
+            Some((last, attempt)) if mem::discriminant(last) == mem::discriminant(class) => {
+                attempt + 1
+            }
+            _ => 1,
+        };
+        self.last_failure = Some((class.clone(), attempt));
+        self.backoff(class).delay(attempt)
+    }
+
+    pub fn on_success(&mut self) {
+        self.last_failure = None;
+    }
+
+    /// Runs `f` until it succeeds or its error class has no retries left.
+    ///
+    /// A transaction whose outcome is unknown is never retried, it may still
+    /// be executed and must be resolved from the journal first.
+    pub async fn retry<T, F, Fut>(&mut self, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut retries = 0;
+        loop {
+            match f().await {
+                Ok(value) => {
+                    self.on_success();
+                    return Ok(value);
+                }
+                Err(e @ AnglerfishError::OutcomeUnknown { .. }) => return Err(e),
+                Err(e) => {
+                    let class = ErrorClass::classify(&e);
+                    if retries >= self.backoff(&class).max_retries {
+                        return Err(e);
+                    }
+                    retries += 1;
+                    let delay = self.on_failure(&class);
+                    warn!(%class, error = %e, retry_in = ?delay, "Retrying");
+                    sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before the attempt following the `attempt`-th consecutive failure,
+    /// shortened by up to `jitter_percent` so that replicas do not retry in step.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.jittered(
+            self.base_delay(attempt),
+            RandomState::new().hash_one(attempt),
+        )
+    }
+
+    /// Delay before jitter: `initial_delay_ms` multiplied after each
+    /// consecutive failure, capped by `max_delay_ms`.
+    pub fn base_delay(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1)
+            .saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(
+            self.initial_delay_ms
+                .saturating_mul(factor as u64)
+                .min(self.max_delay_ms),
+        )
+    }
+
+    /// Removes `random % (jitter + 1)` from `delay`, the jitter being
+    /// `jitter_percent` of the delay.
+    fn jittered(&self, delay: Duration, random: u64) -> Duration {
+        let delay_ms = delay.as_millis() as u64;
+        let jitter_ms = delay_ms.saturating_mul(self.jitter_percent.min(100) as u64) / 100;
+        Duration::from_millis(delay_ms - random % (jitter_ms + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_sdk::types::{base_types::SuiAddress, digests::TransactionDigest};
+
+    use super::*;
+
+    fn backoff(jitter_percent: u32) -> Backoff {
+        Backoff {
+            jitter_percent,
+            ..Backoff::new(500, 30_000, 2, 5)
+        }
+    }
+
+    #[test]
+    fn base_delay_grows_up_to_the_cap() {
+        let cases = [
+            // (attempt, expected delay in ms)
+            (0, 500),
+            (1, 500),
+            (2, 1_000),
+            (3, 2_000),
+            (6, 16_000),
+            (7, 30_000),
+            (64, 30_000),
+            (u32::MAX, 30_000),
+        ];
+        for (attempt, expected) in cases {
+            assert_eq!(
+                backoff(20).base_delay(attempt),
+                Duration::from_millis(expected),
+                "attempt {}",
+                attempt
+            );
+        }
+        // a multiplier of 0 keeps the initial delay
+        let constant = Backoff::new(1_000, 5_000, 0, 0);
+        assert_eq!(constant.base_delay(5), Duration::from_millis(1_000));
+    }
+
+    #[test]
+    fn jitter_shortens_the_delay_within_bounds() {
+        let delay = Duration::from_millis(10_000);
+        let cases = [
+            // (random, expected delay in ms)
+            (0, 10_000),
+            (1, 9_999),
+            (2_000, 8_000),
+            (2_001, 10_000),
+            (u64::MAX, 10_000 - u64::MAX % 2_001),
+        ];
+        for (random, expected) in cases {
+            assert_eq!(
+                backoff(20).jittered(delay, random),
+                Duration::from_millis(expected),
+                "random {}",
+                random
+            );
+        }
+        assert_eq!(backoff(0).jittered(delay, 1_234), delay);
+        assert_eq!(backoff(150).jittered(delay, 10_000), Duration::ZERO);
+    }
+
+    #[test]
+    fn delay_stays_within_the_jitter_bounds() {
+        for attempt in 1..=10 {
+            let base = backoff(20).base_delay(attempt);
+            for _ in 0..100 {
+                let delay = backoff(20).delay(attempt);
+                assert!(delay <= base && delay >= base * 4 / 5, "{:?}", delay);
+            }
+            assert_eq!(backoff(0).delay(attempt), base);
+        }
+    }
+
+    #[test]
+    fn classify_by_error_variant() {
+        let cases = [
+            (
+                AnglerfishError::ObjectLocked("locked".to_string()),
+                ErrorClass::ObjectLocked,
+            ),
+            (
+                AnglerfishError::SharedObjectConflict("congested".to_string()),
+                ErrorClass::SharedObjectConflict,
+            ),
+            (
+                AnglerfishError::SignerUnavailable("503".to_string()),
+                ErrorClass::Transient,
+            ),
+            // only the signer tells a transport failure apart
+            (
+                AnglerfishError::Signer("Remote signer answered 503".to_string()),
+                ErrorClass::Other,
+            ),
+            (
+                AnglerfishError::Reverted("ObjectLockConflict".to_string()),
+                ErrorClass::Other,
+            ),
+            (
+                AnglerfishError::Gas(GasError::InsufficientBalance {
+                    address: SuiAddress::ZERO,
+                    required: 100,
+                    available: 10,
+                    shortfall: 90,
+                }),
+                ErrorClass::GasShortage,
+            ),
+            (
+                AnglerfishError::Gas(GasError::TooFragmented {
+                    address: SuiAddress::ZERO,
+                    required: 100,
+                    coin_count: 300,
+                }),
+                ErrorClass::GasShortage,
+            ),
+            (
+                AnglerfishError::Gas(GasError::BudgetExceeded {
+                    operation: "draw".to_string(),
+                    estimated: 60_000_000,
+                    max_budget: 50_000_000,
+                }),
+                ErrorClass::Other,
+            ),
+            (
+                AnglerfishError::MoveAbort {
+                    module: "phase".to_string(),
+                    function: Some("next_entry".to_string()),
+                    code: 3,
+                    digest: None,
+                },
+                ErrorClass::MoveAbort {
+                    module: "phase".to_string(),
+                    function: Some("next_entry".to_string()),
+                    code: 3,
+                },
+            ),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(ErrorClass::classify(&error), expected, "{}", error);
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_gives_up_after_max_retries() {
+        let mut config = config::Retry::default();
+        config.transient = Backoff::new(0, 0, 1, 2);
+        let mut policy = RetryPolicy::new(config);
+
+        let mut attempts = 0;
+        let result: Result<()> = policy
+            .retry(|| {
+                attempts += 1;
+                async { Err(AnglerfishError::SignerUnavailable("timed out".to_string())) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+
+        let mut attempts = 0;
+        let result: Result<()> = policy
+            .retry(|| {
+                attempts += 1;
+                async { Err(AnglerfishError::Signer("No signer loaded".to_string())) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn retry_stops_at_an_unknown_outcome() {
+        let mut config = config::Retry::default();
+        config.transient = Backoff::new(0, 0, 1, 2);
+        let mut policy = RetryPolicy::new(config);
+
+        let mut attempts = 0;
+        let result: Result<()> = policy
+            .retry(|| {
+                attempts += 1;
+                async {
+                    Err(AnglerfishError::OutcomeUnknown {
+                        digest: TransactionDigest::random(),
+                        error: "connection reset".to_string(),
+                    })
+                }
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(AnglerfishError::OutcomeUnknown { .. })
+        ));
+        assert_eq!(attempts, 1);
+    }
+}
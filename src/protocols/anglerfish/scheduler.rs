@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use crate::config;
+
+use super::engine::Action;
+
+/// When the iterator should carry out an `Action`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// The action can be carried out now.
+    Due,
+    /// The deadline is close: sleep for the given duration, then advance
+    /// without re-reading the phase.
+    FireIn(Duration),
+    /// Sleep for the given duration, then re-read the phase from chain.
+    RecheckIn(Duration),
+}
+
+/// Deadline-aware scheduler.
+///
+/// The scheduler sleeps until shortly before the current phase deadline,
+/// re-checks the on-chain phase once, then sleeps exactly until the deadline.
+/// Waits are capped by the poll interval so that phase changes made on-chain
+/// by someone else are still noticed.
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    poll_interval: Duration,
+    lead_time: Duration,
+    grace: Duration,
+}
+
+impl Scheduler {
+    pub fn new(poll_interval: Duration, lead_time: Duration, grace: Duration) -> Self {
+        Scheduler {
+            poll_interval,
+            lead_time,
+            grace,
+        }
+    }
+
+    pub fn from_config(config: &config::Scheduler) -> Self {
+        Scheduler::new(
+            Duration::from_secs(config.poll_interval_secs),
+            Duration::from_secs(config.lead_time_secs),
+            Duration::from_millis(config.grace_ms),
+        )
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    pub fn schedule(&self, action: &Action, now_ms: u64) -> Schedule {
+        let deadline = match *action {
+            Action::Idle => return Schedule::RecheckIn(self.poll_interval),
+            Action::Execute(_) => return Schedule::Due,
+            Action::WaitUntil(deadline) if now_ms <= deadline => deadline,
+            Action::WaitUntil(_) => return Schedule::Due,
+        };
+
+        // The phase can only be advanced once the on-chain clock is strictly
+        // past the deadline, hence the extra millisecond.
+        let remaining = Duration::from_millis(deadline - now_ms);
+        if remaining <= self.lead_time {
+            Schedule::FireIn(remaining + self.grace + Duration::from_millis(1))
+        } else {
+            Schedule::RecheckIn(self.poll_interval.min(remaining - self.lead_time))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocols::anglerfish::engine::Operation;
+
+    const DEADLINE: u64 = 10_000_000;
+
+    fn scheduler() -> Scheduler {
+        Scheduler::new(
+            Duration::from_secs(30),
+            Duration::from_secs(5),
+            Duration::from_millis(200),
+        )
+    }
+
+    #[test]
+    fn schedule_around_the_deadline() {
+        let fire_in = |ms| Schedule::FireIn(Duration::from_millis(ms));
+        let recheck_in = |ms| Schedule::RecheckIn(Duration::from_millis(ms));
+        let cases = [
+            // (now_ms, expected schedule)
+            (DEADLINE + 1, Schedule::Due),
+            (DEADLINE + 60_000, Schedule::Due),
+            // Deadline reached but not passed yet.
+            (DEADLINE, fire_in(201)),
+            (DEADLINE - 1, fire_in(202)),
+            // Inside the lead time.
+            (DEADLINE - 5_000, fire_in(5_201)),
+            // Before the lead time, capped by the poll interval.
+            (DEADLINE - 5_001, recheck_in(1)),
+            (DEADLINE - 20_000, recheck_in(15_000)),
+            (DEADLINE - 35_000, recheck_in(30_000)),
+            (DEADLINE - 3_600_000, recheck_in(30_000)),
+        ];
+        for (now_ms, expected) in cases {
+            assert_eq!(
+                scheduler().schedule(&Action::WaitUntil(DEADLINE), now_ms),
+                expected,
+                "now_ms = {}",
+                now_ms
+            );
+        }
+    }
+
+    #[test]
+    fn schedule_without_deadline() {
+        assert_eq!(
+            scheduler().schedule(&Action::Idle, DEADLINE),
+            Schedule::RecheckIn(Duration::from_secs(30))
+        );
+        assert_eq!(
+            scheduler().schedule(&Action::Execute(Operation::Draw), DEADLINE),
+            Schedule::Due
+        );
+    }
+}
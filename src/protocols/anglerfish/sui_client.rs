@@ -1,86 +1,382 @@
-use anyhow::{Result, anyhow};
 use sui_sdk::{
-    SUI_COIN_TYPE,
-    rpc_types::SuiTransactionBlockResponseOptions,
+    rpc_types::{
+        SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions,
+    },
     types::{
+        base_types::{ObjectID, SequenceNumber},
         digests::TransactionDigest,
+        gas::GasCostSummary,
         quorum_driver_types::ExecuteTransactionRequestType,
-        transaction::{ProgrammableTransaction, TransactionData},
+        transaction::{ProgrammableTransaction, Transaction, TransactionData},
     },
 };
+use tracing::{Span, info, warn};
 
-use super::client::AnglerfishClient;
+use crate::{
+    error::{AnglerfishError, Result},
+    helper::gas::{GasCoinSelector, GasError},
+    journal::{JournalRecord, summarize_ptb},
+};
+
+use super::{
+    client::AnglerfishClient,
+    engine::Operation,
+    types::{events::AnglerfishEvent, phase_info::PhaseInfo},
+};
+
+/// Result of an executed transaction whose effects are `Success`.
+#[derive(Debug, Clone)]
+pub struct ExecutionResult {
+    pub digest: TransactionDigest,
+    pub status: SuiExecutionStatus,
+    pub gas_used: GasCostSummary,
+    /// Objects mutated by the transaction, with their new versions.
+    pub mutated: Vec<(ObjectID, SequenceNumber)>,
+    /// Anglerfish events emitted by the transaction.
+    pub events: Vec<AnglerfishEvent>,
+}
 
 #[async_trait::async_trait]
 pub trait AnglerfishSuiClient {
-    async fn execute(&self, pt: ProgrammableTransaction) -> Result<TransactionDigest>;
+    /// Dry runs, signs and executes `pt`. `phase_info` is the phase the
+    /// transaction was built from, it is only used to journal the attempt.
+    async fn execute(
+        &self,
+        operation: Operation,
+        phase_info: &PhaseInfo,
+        pt: ProgrammableTransaction,
+    ) -> Result<ExecutionResult>;
 }
 
 #[async_trait::async_trait]
 impl AnglerfishSuiClient for AnglerfishClient {
-    async fn execute(&self, pt: ProgrammableTransaction) -> Result<TransactionDigest> {
-        let wallet = self.wallet();
-        let sender_address = wallet
-            .get_addresses()
-            .first()
-            .ok_or_else(|| anyhow!("No address found in the wallet"))?
-            .clone();
-
-        // we need to find the coin we will use as gas
-        let sui_coins = self
-            .sui_client()
-            .coin_read_api()
-            .get_coins(
-                sender_address,
-                Option::Some(SUI_COIN_TYPE.to_string()),
-                None,
-                None,
+    async fn execute(
+        &self,
+        operation: Operation,
+        phase_info: &PhaseInfo,
+        pt: ProgrammableTransaction,
+    ) -> Result<ExecutionResult> {
+        self.execute_transaction(operation, phase_info, pt).await
+    }
+}
+
+impl AnglerfishClient {
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            action = %operation,
+            round = phase_info.current_round_number,
+            phase = ?phase_info.current_phase,
+            digest,
+        )
+    )]
+    async fn execute_transaction(
+        &self,
+        operation: Operation,
+        phase_info: &PhaseInfo,
+        pt: ProgrammableTransaction,
+    ) -> Result<ExecutionResult> {
+        let sender_address = self.sender_address()?;
+        let gas_config = &self.config().gas;
+        let max_gas_budget = gas_config.max_budget.for_operation(operation);
+        let gas_price = self
+            .observe_rpc(
+                "get_reference_gas_price",
+                self.sui_client().read_api().get_reference_gas_price(),
             )
             .await?;
-        let gas_coin = sui_coins.data.into_iter().next().unwrap();
+
+        // check if the transaction is valid and estimate its gas cost
+        // this is a dry run, so it won't be submitted to the network
+        // without gas coins the node pays the dry run with a mock coin
+        let ptb_summary = summarize_ptb(&pt);
+        let dry_run_tx_data = TransactionData::new_programmable(
+            sender_address,
+            vec![],
+            pt.clone(),
+            max_gas_budget,
+            gas_price,
+        );
+        let ret = self
+            .observe_rpc(
+                "dry_run_transaction_block",
+                self.sui_client()
+                    .read_api()
+                    .dry_run_transaction_block(dry_run_tx_data),
+            )
+            .await?;
+
+        let estimated_gas = gas_used(ret.effects.gas_cost_summary());
+        let dry_run_error = match ret.effects.status() {
+            SuiExecutionStatus::Failure { error } => Some(error.clone()),
+            SuiExecutionStatus::Success => None,
+        };
+        self.record(
+            operation,
+            phase_info,
+            JournalRecord::DryRun {
+                ptb: ptb_summary,
+                estimated_gas,
+                error: dry_run_error.clone(),
+            },
+        )?;
+        if let Some(error) = dry_run_error {
+            if let Some(metrics) = self.metrics() {
+                metrics.dry_run_abort(phase_info.current_phase);
+            }
+            return Err(AnglerfishError::from_execution_error(&error, None));
+        }
+
+        let gas_budget = estimate_gas_budget(
+            estimated_gas,
+            gas_config.margin_percent,
+            gas_config.min_budget,
+            max_gas_budget,
+        );
+        if gas_budget < estimated_gas {
+            return Err(GasError::BudgetExceeded {
+                operation: operation.to_string(),
+                estimated: estimated_gas,
+                max_budget: max_gas_budget,
+            }
+            .into());
+        }
+
+        // pick the coins that will pay for gas, merging them if one is not enough
+        let gas_selection = self
+            .observe_rpc(
+                "get_coins",
+                self.sui_client()
+                    .select_gas_coins(sender_address, gas_budget),
+            )
+            .await;
+        if let (Some(metrics), Ok(gas_selection)) = (self.metrics(), &gas_selection) {
+            metrics.signer_balance(gas_selection.balance);
+        }
+        let gas_selection = gas_selection?;
 
         // using the PTB that we just constructed, create the transaction data
         // that we will submit to the network
-        let max_gas_budget = 5_000_000; // 1 Hop swap can take 5m gas budget
-        let gas_price = self
-            .sui_client()
-            .read_api()
-            .get_reference_gas_price()
-            .await?;
         let tx_data = TransactionData::new_programmable(
             sender_address,
-            vec![gas_coin.object_ref()],
+            gas_selection.coins,
             pt,
-            max_gas_budget,
+            gas_budget,
             gas_price,
         );
 
-        // check if the transaction is valid
-        // this is a dry run, so it won't be submitted to the network
-        let ret = self
-            .sui_client()
-            .read_api()
-            .dry_run_transaction_block(tx_data.clone())
-            .await?;
+        let signature = self.signer()?.sign(&tx_data).await?;
+        let signed_tx = Transaction::from_data(tx_data, vec![signature]);
+        Span::current().record("digest", tracing::field::display(signed_tx.digest()));
 
-        if ret.execution_error_source.is_none() {
-            let signed_tx = wallet.sign_transaction(&tx_data);
-            let transaction_response = self
-                .sui_client()
-                .quorum_driver_api()
-                .execute_transaction_block(
-                    signed_tx,
-                    SuiTransactionBlockResponseOptions::default(),
-                    Some(ExecuteTransactionRequestType::WaitForLocalExecution),
-                )
-                .await?;
-
-            return Ok(transaction_response.digest);
-        } else {
-            return Err(anyhow!(
-                "Transaction is reverted: {:?}",
-                ret.execution_error_source
+        // the RPCs and the signer above may have taken long enough for the
+        // lease to be lost, another leader could then submit with the same cap
+        if self.leader().is_some_and(|leader| !leader.is_leader()) {
+            return Err(AnglerfishError::Lease(
+                "Leader lease expiring, not submitting".to_string(),
             ));
         }
+
+        // the digest is journaled before submission, so a crash while waiting
+        // for the effects leaves a record to resolve on restart
+        self.record(
+            operation,
+            phase_info,
+            JournalRecord::Submitted {
+                digest: *signed_tx.digest(),
+                gas_budget,
+            },
+        )?;
+
+        let phase = phase_info.current_phase;
+        if let Some(metrics) = self.metrics() {
+            metrics.transition_attempt(phase);
+        }
+        let result = self
+            .submit_transaction(
+                operation,
+                phase_info,
+                signed_tx,
+                estimated_gas,
+                gas_budget,
+                gas_selection.balance,
+            )
+            .await;
+        if let Some(metrics) = self.metrics() {
+            metrics.transition_result(phase, result.is_ok());
+        }
+        result
+    }
+
+    /// Submits `signed_tx`, journaled as submitted, and reads its effects.
+    /// `balance` is the total SUI balance of the sender when the gas coins
+    /// were selected, see `GasSelection::balance`.
+    async fn submit_transaction(
+        &self,
+        operation: Operation,
+        phase_info: &PhaseInfo,
+        signed_tx: Transaction,
+        estimated_gas: u64,
+        gas_budget: u64,
+        balance: u64,
+    ) -> Result<ExecutionResult> {
+        let digest = *signed_tx.digest();
+        let transaction_response = self
+            .observe_rpc(
+                "execute_transaction_block",
+                self.sui_client()
+                    .quorum_driver_api()
+                    .execute_transaction_block(
+                        signed_tx,
+                        SuiTransactionBlockResponseOptions::default()
+                            .with_effects()
+                            .with_events(),
+                        Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+                    ),
+            )
+            .await
+            .map_err(|e| AnglerfishError::from_submission_error(e, digest));
+        let transaction_response = match transaction_response {
+            Ok(transaction_response) => transaction_response,
+            // a rejected transaction will not be executed, it can be retried
+            Err(
+                e @ (AnglerfishError::ObjectLocked(_) | AnglerfishError::SharedObjectConflict(_)),
+            ) => {
+                self.record(
+                    operation,
+                    phase_info,
+                    JournalRecord::Abandoned {
+                        digest,
+                        reason: e.to_string(),
+                    },
+                )?;
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
+
+        // the dry run may pass while the execution fails, e.g. when another
+        // transaction touched a shared object in between
+        let effects = transaction_response
+            .effects
+            .ok_or_else(|| AnglerfishError::missing_effects(digest))?;
+        let gas_summary = effects.gas_cost_summary().clone();
+        info!(
+            estimated_gas,
+            gas_budget,
+            gas_used = gas_used(&gas_summary),
+            "Transaction executed"
+        );
+
+        if let Some(metrics) = self.metrics() {
+            let spent = gas_used(&gas_summary).saturating_sub(gas_summary.storage_rebate);
+            metrics.gas_spent(spent);
+            metrics.signer_balance(balance.saturating_sub(spent));
+        }
+
+        let status = effects.status().clone();
+        self.record(
+            operation,
+            phase_info,
+            JournalRecord::Executed {
+                digest,
+                status: status.to_string(),
+                gas_used: gas_used(&gas_summary),
+            },
+        )?;
+        if let SuiExecutionStatus::Failure { error } = &status {
+            return Err(AnglerfishError::from_execution_error(error, Some(digest)));
+        }
+
+        // the transaction is executed at this point, an event which cannot be
+        // decoded is only reported
+        let mut events = vec![];
+        for event in transaction_response
+            .events
+            .iter()
+            .flat_map(|tx_events| &tx_events.data)
+        {
+            match AnglerfishEvent::try_from_sui_event(event, self.original_package_id()) {
+                Ok(Some(decoded)) => {
+                    info!(event = ?decoded, "Event emitted");
+                    events.push(decoded);
+                }
+                Ok(None) => {}
+                Err(e) => warn!(error = %e, "Failed to decode event"),
+            }
+        }
+
+        Ok(ExecutionResult {
+            digest,
+            status,
+            gas_used: gas_summary,
+            mutated: effects
+                .mutated()
+                .iter()
+                .map(|object| (object.reference.object_id, object.reference.version))
+                .collect(),
+            events,
+        })
+    }
+}
+
+/// Gas charged before the storage rebate, which is what the budget must cover.
+pub(crate) fn gas_used(summary: &GasCostSummary) -> u64 {
+    summary.computation_cost + summary.storage_cost
+}
+
+/// Adds the safety margin to the estimated gas and clamps the result to the
+/// configured bounds.
+fn estimate_gas_budget(estimated_gas: u64, margin_percent: u64, min: u64, max: u64) -> u64 {
+    let with_margin =
+        estimated_gas.saturating_add(estimated_gas.saturating_mul(margin_percent) / 100);
+    with_margin.max(min).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: u64 = 2_000_000;
+    const MAX: u64 = 50_000_000;
+
+    #[test]
+    fn estimate_gas_budget_bounds() {
+        let cases = [
+            // (estimated gas, margin percent, expected budget)
+            (10_000_000, 20, 12_000_000),
+            (10_000_000, 0, 10_000_000),
+            (10_000_000, 100, 20_000_000),
+            // Floor.
+            (0, 20, MIN),
+            (1_000_000, 20, MIN),
+            (MIN, 0, MIN),
+            // Ceiling, the margin is cut first.
+            (45_000_000, 20, MAX),
+            (MAX, 20, MAX),
+            // Above the ceiling, the caller rejects a budget below the estimate.
+            (60_000_000, 20, MAX),
+            (u64::MAX, 20, MAX),
+        ];
+        for (estimated_gas, margin_percent, expected) in cases {
+            assert_eq!(
+                estimate_gas_budget(estimated_gas, margin_percent, MIN, MAX),
+                expected,
+                "{} gas with a {}% margin",
+                estimated_gas,
+                margin_percent
+            );
+        }
+    }
+
+    #[test]
+    fn estimate_gas_budget_saturates() {
+        assert_eq!(estimate_gas_budget(u64::MAX, 20, 0, u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn gas_used_excludes_the_storage_rebate() {
+        let summary = GasCostSummary::new(1_000, 2_000, 1_500, 0);
+        assert_eq!(gas_used(&summary), 3_000);
     }
 }
@@ -0,0 +1,320 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sui_sdk::{
+    rpc_types::SuiEvent,
+    types::base_types::{ObjectID, SuiAddress},
+};
+
+use crate::error::{AnglerfishError, Result};
+
+use super::phase_info::Phase;
+
+/// Rust representation of `anglerfish::phase::PhaseChangedEvent`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseChangedEvent {
+    pub round_number: u64,
+    pub previous_phase: Phase,
+    pub current_phase: Phase,
+    pub timestamp_ms: u64,
+}
+
+/// Rust representation of `anglerfish::prize_pool::RoundStartedEvent`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundStartedEvent {
+    pub round_number: u64,
+    pub round_id: ObjectID,
+    pub timestamp_ms: u64,
+}
+
+/// Rust representation of `anglerfish::prize_pool::DrawEvent`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrawEvent {
+    pub round_number: u64,
+    pub round_id: ObjectID,
+    pub total_tickets: u64,
+    pub winning_ticket_index: u64,
+    /// `None` if the winning ticket was not sold, the prize then stays in the pool.
+    pub winner: Option<SuiAddress>,
+    pub prize_amount: u64,
+    pub timestamp_ms: u64,
+}
+
+/// Rust representation of `anglerfish::prize_pool::DistributeEvent`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributeEvent {
+    pub round_number: u64,
+    pub round_id: ObjectID,
+    pub winner: Option<SuiAddress>,
+    pub prize_amount: u64,
+    pub lp_fee_amount: u64,
+    pub protocol_fee_amount: u64,
+    pub timestamp_ms: u64,
+}
+
+/// Types of the events emitted by the Anglerfish package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnglerfishEventType {
+    PhaseChanged,
+    RoundStarted,
+    Draw,
+    Distribute,
+}
+
+impl AnglerfishEventType {
+    const ALL: [AnglerfishEventType; 4] = [
+        AnglerfishEventType::PhaseChanged,
+        AnglerfishEventType::RoundStarted,
+        AnglerfishEventType::Draw,
+        AnglerfishEventType::Distribute,
+    ];
+
+    /// Move module emitting the event.
+    pub fn module(&self) -> &'static str {
+        match self {
+            AnglerfishEventType::PhaseChanged => "phase",
+            AnglerfishEventType::RoundStarted
+            | AnglerfishEventType::Draw
+            | AnglerfishEventType::Distribute => "prize_pool",
+        }
+    }
+
+    /// Name of the Move struct of the event.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnglerfishEventType::PhaseChanged => "PhaseChangedEvent",
+            AnglerfishEventType::RoundStarted => "RoundStartedEvent",
+            AnglerfishEventType::Draw => "DrawEvent",
+            AnglerfishEventType::Distribute => "DistributeEvent",
+        }
+    }
+}
+
+/// Decoded event of the Anglerfish package.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnglerfishEvent {
+    PhaseChanged(PhaseChangedEvent),
+    RoundStarted(RoundStartedEvent),
+    Draw(DrawEvent),
+    Distribute(DistributeEvent),
+}
+
+impl AnglerfishEvent {
+    /// Decodes `event` from its BCS content. Returns `None` if `event` is not
+    /// one of the Anglerfish events, which are matched by module and name
+    /// under `package`, the original id of the Anglerfish package.
+    pub fn try_from_sui_event(
+        event: &SuiEvent,
+        package: ObjectID,
+    ) -> Result<Option<AnglerfishEvent>> {
+        if ObjectID::from(event.type_.address) != package {
+            return Ok(None);
+        }
+        let event_type = AnglerfishEventType::ALL.into_iter().find(|event_type| {
+            event.type_.module.as_str() == event_type.module()
+                && event.type_.name.as_str() == event_type.name()
+        });
+        let decoded = match event_type {
+            Some(AnglerfishEventType::PhaseChanged) => {
+                AnglerfishEvent::PhaseChanged(decode(event)?)
+            }
+            Some(AnglerfishEventType::RoundStarted) => {
+                AnglerfishEvent::RoundStarted(decode(event)?)
+            }
+            Some(AnglerfishEventType::Draw) => AnglerfishEvent::Draw(decode(event)?),
+            Some(AnglerfishEventType::Distribute) => AnglerfishEvent::Distribute(decode(event)?),
+            None => return Ok(None),
+        };
+        Ok(Some(decoded))
+    }
+
+    pub fn round_number(&self) -> u64 {
+        match self {
+            AnglerfishEvent::PhaseChanged(event) => event.round_number,
+            AnglerfishEvent::RoundStarted(event) => event.round_number,
+            AnglerfishEvent::Draw(event) => event.round_number,
+            AnglerfishEvent::Distribute(event) => event.round_number,
+        }
+    }
+}
+
+fn decode<T: DeserializeOwned>(event: &SuiEvent) -> Result<T> {
+    bcs::from_bytes(event.bcs.bytes()).map_err(|e| {
+        AnglerfishError::EventDecode(format!("{} of {}: {}", event.type_, event.id.tx_digest, e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sui_sdk::{
+        rpc_types::BcsEvent,
+        types::{Identifier, digests::TransactionDigest, event::EventID, parse_sui_struct_tag},
+    };
+
+    use super::*;
+
+    const ROUND: u64 = 7;
+    const TIMESTAMP_MS: u64 = 1_700_000_000_000;
+
+    fn sui_event(package: ObjectID, type_: &str, bcs: Vec<u8>) -> SuiEvent {
+        let type_ = parse_sui_struct_tag(&format!("{}::{}", package, type_)).unwrap();
+        SuiEvent {
+            id: EventID {
+                tx_digest: TransactionDigest::random(),
+                event_seq: 0,
+            },
+            package_id: package,
+            transaction_module: Identifier::new(type_.module.as_str()).unwrap(),
+            sender: SuiAddress::random_for_testing_only(),
+            type_,
+            parsed_json: json!({}),
+            bcs: BcsEvent::new(bcs),
+            timestamp_ms: Some(TIMESTAMP_MS),
+        }
+    }
+
+    /// BCS of a Move `Option<address>`: a vector of zero or one address.
+    fn option_address(address: Option<SuiAddress>) -> Vec<u8> {
+        match address {
+            Some(address) => [&[1u8][..], address.as_ref()].concat(),
+            None => vec![0],
+        }
+    }
+
+    fn decode(package: ObjectID, type_: &str, bcs: Vec<u8>) -> Option<AnglerfishEvent> {
+        AnglerfishEvent::try_from_sui_event(&sui_event(package, type_, bcs), package).unwrap()
+    }
+
+    #[test]
+    fn decode_phase_changed() {
+        let package = ObjectID::random();
+        // round number, previous and current phase variants, timestamp
+        let bcs = [
+            &ROUND.to_le_bytes()[..],
+            &[1, 2],
+            &TIMESTAMP_MS.to_le_bytes(),
+        ]
+        .concat();
+        match decode(package, "phase::PhaseChangedEvent", bcs) {
+            Some(AnglerfishEvent::PhaseChanged(event)) => {
+                assert_eq!(event.round_number, ROUND);
+                assert_eq!(event.previous_phase, Phase::LiquidityProviding);
+                assert_eq!(event.current_phase, Phase::Ticketing);
+                assert_eq!(event.timestamp_ms, TIMESTAMP_MS);
+            }
+            event => panic!("expected a phase change, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn decode_round_started() {
+        let package = ObjectID::random();
+        let round_id = ObjectID::random();
+        let bcs = [
+            &ROUND.to_le_bytes()[..],
+            round_id.as_ref(),
+            &TIMESTAMP_MS.to_le_bytes(),
+        ]
+        .concat();
+        match decode(package, "prize_pool::RoundStartedEvent", bcs) {
+            Some(AnglerfishEvent::RoundStarted(event)) => {
+                assert_eq!(event.round_number, ROUND);
+                assert_eq!(event.round_id, round_id);
+                assert_eq!(event.timestamp_ms, TIMESTAMP_MS);
+            }
+            event => panic!("expected a round start, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn decode_draw_with_and_without_winner() {
+        let package = ObjectID::random();
+        let round_id = ObjectID::random();
+        for winner in [Some(SuiAddress::random_for_testing_only()), None] {
+            let bcs = [
+                &ROUND.to_le_bytes()[..],
+                round_id.as_ref(),
+                &120u64.to_le_bytes(),
+                &42u64.to_le_bytes(),
+                &option_address(winner),
+                &5_000u64.to_le_bytes(),
+                &TIMESTAMP_MS.to_le_bytes(),
+            ]
+            .concat();
+            match decode(package, "prize_pool::DrawEvent", bcs) {
+                Some(AnglerfishEvent::Draw(event)) => {
+                    assert_eq!(event.round_number, ROUND);
+                    assert_eq!(event.round_id, round_id);
+                    assert_eq!(event.total_tickets, 120);
+                    assert_eq!(event.winning_ticket_index, 42);
+                    assert_eq!(event.winner, winner);
+                    assert_eq!(event.prize_amount, 5_000);
+                    assert_eq!(event.timestamp_ms, TIMESTAMP_MS);
+                }
+                event => panic!("expected a draw, got {:?}", event),
+            }
+        }
+    }
+
+    #[test]
+    fn decode_distribute() {
+        let package = ObjectID::random();
+        let round_id = ObjectID::random();
+        let winner = SuiAddress::random_for_testing_only();
+        let bcs = [
+            &ROUND.to_le_bytes()[..],
+            round_id.as_ref(),
+            &option_address(Some(winner)),
+            &5_000u64.to_le_bytes(),
+            &250u64.to_le_bytes(),
+            &100u64.to_le_bytes(),
+            &TIMESTAMP_MS.to_le_bytes(),
+        ]
+        .concat();
+        match decode(package, "prize_pool::DistributeEvent", bcs) {
+            Some(AnglerfishEvent::Distribute(event)) => {
+                assert_eq!(event.round_number, ROUND);
+                assert_eq!(event.round_id, round_id);
+                assert_eq!(event.winner, Some(winner));
+                assert_eq!(event.prize_amount, 5_000);
+                assert_eq!(event.lp_fee_amount, 250);
+                assert_eq!(event.protocol_fee_amount, 100);
+                assert_eq!(event.timestamp_ms, TIMESTAMP_MS);
+            }
+            event => panic!("expected a distribution, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn events_of_other_packages_or_types_are_ignored() {
+        let package = ObjectID::random();
+        let bcs = [
+            &ROUND.to_le_bytes()[..],
+            &[1, 2],
+            &TIMESTAMP_MS.to_le_bytes(),
+        ]
+        .concat();
+
+        let foreign = sui_event(ObjectID::random(), "phase::PhaseChangedEvent", bcs.clone());
+        assert!(
+            AnglerfishEvent::try_from_sui_event(&foreign, package)
+                .unwrap()
+                .is_none()
+        );
+        assert!(decode(package, "phase::OtherEvent", bcs).is_none());
+    }
+
+    #[test]
+    fn truncated_event_fails_to_decode() {
+        let package = ObjectID::random();
+        let event = sui_event(
+            package,
+            "prize_pool::DrawEvent",
+            ROUND.to_le_bytes().to_vec(),
+        );
+        assert!(matches!(
+            AnglerfishEvent::try_from_sui_event(&event, package),
+            Err(AnglerfishError::EventDecode(_))
+        ));
+    }
+}
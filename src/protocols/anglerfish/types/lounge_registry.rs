@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use sui_sdk::types::{collection_types::Table, id::UID};
+
+/// Rust representation of `anglerfish::lounge::LoungeRegistry`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoungeRegistry {
+    pub id: UID,
+    /// Lounges holding the prizes to claim, keyed by round number.
+    pub lounges: Table,
+}
@@ -1,7 +1,12 @@
-use anyhow::Result;
 use sui_sdk::rpc_types::SuiObjectData;
 
+use crate::error::Result;
+
+pub mod events;
+pub mod lounge_registry;
 pub mod phase_info;
+pub mod pool_registry;
+pub mod prize_pool;
 pub mod round;
 pub mod round_registry;
 
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use sui_sdk::types::id::UID;
 
 /// Rust representation of `anglerfish::phase::Phase`.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Phase {
     Uninitialized,
     LiquidityProviding,
@@ -29,3 +29,17 @@ pub struct PhaseDurations {
     pub liquidity_providing_duration: u64,
     pub ticketing_duration: u64,
 }
+
+impl PhaseInfo {
+    /// Returns the timestamp (ms) after which the current phase can be advanced,
+    /// or `None` if the current phase is not time-bound.
+    pub fn current_phase_deadline(&self) -> Option<u64> {
+        match self.current_phase {
+            Phase::LiquidityProviding => {
+                Some(self.current_phase_at + self.durations.liquidity_providing_duration)
+            }
+            Phase::Ticketing => Some(self.current_phase_at + self.durations.ticketing_duration),
+            _ => None,
+        }
+    }
+}
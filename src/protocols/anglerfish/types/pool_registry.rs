@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use sui_sdk::types::{base_types::ObjectID, collection_types::VecMap, id::UID};
+
+/// Rust representation of `anglerfish::pool::PoolRegistry<T>`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PoolRegistry {
+    pub id: UID,
+    /// Liquidity pools, keyed by their risk ratio in basis points.
+    pub pools: VecMap<u64, ObjectID>,
+    pub is_deposit_enabled: bool,
+}
+
+impl PoolRegistry {
+    /// Returns the registered liquidity pools as `(risk_ratio_bps, pool_id)`.
+    pub fn pools(&self) -> Vec<(u64, ObjectID)> {
+        self.pools
+            .contents
+            .iter()
+            .map(|entry| (entry.key, entry.value))
+            .collect()
+    }
+}
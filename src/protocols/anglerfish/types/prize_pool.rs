@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use sui_sdk::types::{balance::Balance, id::UID};
+
+/// Rust representation of `anglerfish::prize_pool::PrizePool<T>`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrizePool {
+    pub id: UID,
+    /// Prize reserve of the current round.
+    pub reserves: Balance,
+    pub ticket_price: u64,
+    pub lp_fee_bps: u64,
+    pub protocol_fee_bps: u64,
+    /// Protocol fees collected and not withdrawn yet.
+    pub treasury_reserves: Balance,
+}
+
+impl PrizePool {
+    /// Prize reserve of the current round, in the smallest unit of the pool coin.
+    pub fn reserves(&self) -> u64 {
+        self.reserves.value()
+    }
+
+    pub fn treasury_reserves(&self) -> u64 {
+        self.treasury_reserves.value()
+    }
+}
@@ -1,51 +1,112 @@
-use std::str::FromStr;
+use std::{ops::Range, str::FromStr};
 
-use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use sui_sdk::{
     rpc_types::{SuiObjectData, SuiParsedData},
     types::{
         base_types::{ObjectID, SuiAddress},
+        collection_types::TableVec,
         id::UID,
     },
 };
 
+use crate::error::{AnglerfishError, Result};
+
 use super::FieldsExtractor;
 
-/// Rust representation of `anglerfish::round::Round`.
-#[derive(Debug, Serialize, Deserialize)]
+/// Rust representation of `anglerfish::round::Purchase`.
+///
+/// The purchase owns the tickets `start_index..start_index + ticket_count`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Purchase {
     pub address: SuiAddress,
     pub ticket_count: u64,
     pub start_index: u64,
 }
 
+impl Purchase {
+    /// Ticket indexes owned by the purchase.
+    pub fn tickets(&self) -> Range<u64> {
+        self.start_index..self.start_index + self.ticket_count
+    }
+}
+
 /// Rust representation of `anglerfish::round::Round`.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Round {
     pub id: UID,
+    pub round_number: u64,
+    pub total_tickets: u64,
+    /// Purchases in buying order, the tickets are numbered across them.
+    pub purchases: TableVec,
+    pub winner: Option<SuiAddress>,
+    pub winning_ticket_index: Option<u64>,
+    pub prize_amount: u64,
+    pub started_at: u64,
+    pub drawn_at: Option<u64>,
+    pub distributed_at: Option<u64>,
+}
+
+impl Round {
+    pub fn round_id(&self) -> ObjectID {
+        self.id.id.bytes
+    }
+
+    pub fn is_drawn(&self) -> bool {
+        self.drawn_at.is_some()
+    }
+
+    pub fn is_distributed(&self) -> bool {
+        self.distributed_at.is_some()
+    }
+}
+
+/// Entry of `RoundRegistry.rounds`, mapping a round number to the id of its
+/// `Round` object.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RoundEntry {
+    pub round_number: u64,
+    pub round_id: ObjectID,
 }
 
-impl FieldsExtractor for Round {
-    type T = Round;
+impl FieldsExtractor for RoundEntry {
+    type T = RoundEntry;
 
-    fn try_from_sui_obj_data(data: SuiObjectData) -> Result<Round> {
-        let content_data = data
-            .content
-            .ok_or_else(|| anyhow!("Fetched object but no data was returned"))?;
+    fn try_from_sui_obj_data(data: SuiObjectData) -> Result<RoundEntry> {
+        let content_data = data.content.ok_or_else(|| {
+            AnglerfishError::ObjectDecode("Fetched object but no data was returned".to_string())
+        })?;
 
         let parse_move_obj = match content_data {
             SuiParsedData::MoveObject(parse_move_obj) => parse_move_obj,
-            _ => return Err(anyhow!("Fetched object is not a Move Object")),
+            _ => {
+                return Err(AnglerfishError::ObjectDecode(
+                    "Fetched object is not a Move Object".to_string(),
+                ));
+            }
         };
 
-        let id = parse_move_obj
-            .fields
-            .field_value("value")
-            .ok_or(anyhow!("Failed to extract 'id' field from Round"))?;
+        let round_number = parse_move_obj.fields.field_value("name").ok_or_else(|| {
+            AnglerfishError::ObjectDecode(
+                "Failed to extract 'name' field from the round entry".to_string(),
+            )
+        })?;
+        let round_number = u64::from_str(&round_number.to_string()).map_err(|e| {
+            AnglerfishError::ObjectDecode(format!("Invalid round number {}: {}", round_number, e))
+        })?;
+
+        let round_id = parse_move_obj.fields.field_value("value").ok_or_else(|| {
+            AnglerfishError::ObjectDecode(
+                "Failed to extract 'value' field from the round entry".to_string(),
+            )
+        })?;
+        let round_id = ObjectID::from_str(&round_id.to_string()).map_err(|e| {
+            AnglerfishError::ObjectDecode(format!("Invalid Round id {}: {}", round_id, e))
+        })?;
 
-        Ok(Round {
-            id: UID::new(ObjectID::from_str(&id.to_string()).unwrap()),
+        Ok(RoundEntry {
+            round_number,
+            round_id,
         })
     }
 }
@@ -0,0 +1,166 @@
+use sui_sdk::{
+    rpc_types::{SuiObjectDataOptions, SuiObjectResponse},
+    types::{
+        base_types::{ObjectID, ObjectType, SuiAddress},
+        object::Owner,
+    },
+};
+
+use crate::error::{AnglerfishError, Result};
+
+use super::client::AnglerfishClient;
+
+/// Expected ownership of a configured object.
+enum Ownership {
+    Shared,
+    OwnedBy(SuiAddress),
+}
+
+/// AnglerfishConfigValidator trait
+/// This trait checks the loaded configuration against the on-chain state, so a
+/// wrong id is reported before any transaction is submitted.
+#[async_trait::async_trait]
+pub trait AnglerfishConfigValidator {
+    async fn validate(&self) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl AnglerfishConfigValidator for AnglerfishClient {
+    async fn validate(&self) -> Result<()> {
+        let objects = self.objects();
+        let sender_address = self.sender_address()?;
+
+        let checks = [
+            (
+                "phase_info_id",
+                objects.phase_info_id,
+                "PhaseInfo",
+                Ownership::Shared,
+            ),
+            (
+                "round_registry_id",
+                objects.round_registry_id,
+                "RoundRegistry",
+                Ownership::Shared,
+            ),
+            (
+                "prize_pool_id",
+                objects.prize_pool_id,
+                "PrizePool",
+                Ownership::Shared,
+            ),
+            (
+                "pool_registry_id",
+                objects.pool_registry_id,
+                "PoolRegistry",
+                Ownership::Shared,
+            ),
+            (
+                "lounge_registry_id",
+                objects.lounge_registry_id,
+                "LoungeRegistry",
+                Ownership::Shared,
+            ),
+            (
+                "cap_id",
+                self.iterator_cap_id(),
+                "IteratorCap",
+                Ownership::OwnedBy(sender_address),
+            ),
+        ];
+
+        let mut errors = vec![];
+        for (field, object_id, struct_name, ownership) in checks {
+            if let Err(e) = self
+                .validate_object(object_id, struct_name, ownership)
+                .await
+            {
+                errors.push(format!("{} ({}): {}", field, object_id, e));
+            }
+        }
+
+        let coin_type = self.pool_coin_type();
+        match self
+            .sui_client()
+            .coin_read_api()
+            .get_coin_metadata(coin_type.clone())
+            .await
+        {
+            Ok(Some(_)) => {}
+            Ok(None) => errors.push(format!("coin_type ({}): no coin metadata found", coin_type)),
+            Err(e) => errors.push(format!("coin_type ({}): {}", coin_type, e)),
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AnglerfishError::Config(format!(
+                "Invalid configuration:\n  - {}",
+                errors.join("\n  - ")
+            )))
+        }
+    }
+}
+
+impl AnglerfishClient {
+    /// Checks that the object exists, is a `struct_name` of the configured
+    /// package and has the expected owner. Types keep the id of the package
+    /// which defined them across upgrades, hence `original_package_id`.
+    async fn validate_object(
+        &self,
+        object_id: ObjectID,
+        struct_name: &str,
+        ownership: Ownership,
+    ) -> Result<()> {
+        let object: SuiObjectResponse = self
+            .sui_client()
+            .read_api()
+            .get_object_with_options(
+                object_id,
+                SuiObjectDataOptions::default().with_type().with_owner(),
+            )
+            .await?;
+
+        if object.error.is_some() {
+            return Err(AnglerfishError::ObjectNotFound(object_id));
+        }
+        let data = object
+            .data
+            .ok_or(AnglerfishError::ObjectNotFound(object_id))?;
+
+        let object_type = data
+            .type_
+            .ok_or_else(|| AnglerfishError::ObjectDecode("Object has no type".to_string()))?;
+        let type_matches = match &object_type {
+            ObjectType::Struct(move_object_type) => {
+                ObjectID::from(move_object_type.address()) == self.original_package_id()
+                    && move_object_type.name().as_str() == struct_name
+            }
+            ObjectType::Package => false,
+        };
+        if !type_matches {
+            return Err(AnglerfishError::Config(format!(
+                "expected a {}::<module>::{} but found {}",
+                self.original_package_id(),
+                struct_name,
+                object_type
+            )));
+        }
+
+        let owner = data.owner.ok_or_else(|| {
+            AnglerfishError::ObjectDecode("Object has no owner fields".to_string())
+        })?;
+        match (ownership, owner) {
+            (Ownership::Shared, Owner::Shared { .. }) => Ok(()),
+            (Ownership::Shared, owner) => Err(AnglerfishError::Config(format!(
+                "expected a shared object but owner is {}",
+                owner
+            ))),
+            (Ownership::OwnedBy(address), Owner::AddressOwner(owner)) if owner == address => Ok(()),
+            (Ownership::OwnedBy(address), owner) => Err(AnglerfishError::Config(format!(
+                "expected an object owned by the signer {} but owner is {}",
+                address, owner
+            ))),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::Serialize;
+use sui_sdk::SuiClient;
+use tokio::net::TcpListener;
+use tracing::info;
+
+use crate::{health::Health, metrics::Metrics};
+
+/// State shared by the HTTP endpoints.
+#[derive(Clone)]
+pub struct ServerState {
+    pub metrics: Arc<Metrics>,
+    pub health: Arc<Health>,
+    /// Client used by `/readyz` to check the RPC and the signer balance.
+    pub sui_client: SuiClient,
+    /// Signer balance below which the iterator is not ready, in MIST.
+    pub min_balance_mist: u64,
+}
+
+/// Body of the `/readyz` endpoint.
+#[derive(Debug, Serialize)]
+struct Readiness {
+    ready: bool,
+    rpc_reachable: bool,
+    signer_loaded: bool,
+    config_validated: bool,
+    gas_balance_ok: bool,
+    balance_mist: Option<u128>,
+    error: Option<String>,
+}
+
+/// Serves the monitoring endpoints until the listener fails.
+pub async fn serve(listen_address: SocketAddr, state: ServerState) -> std::io::Result<()> {
+    let app = Router::new()
+        .route("/metrics", get(metrics))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/status", get(status))
+        .with_state(state);
+
+    let listener = TcpListener::bind(listen_address).await?;
+    info!(%listen_address, "Serving metrics and health endpoints");
+    axum::serve(listener, app).await
+}
+
+async fn metrics(State(state): State<ServerState>) -> Response {
+    match state.metrics.gather() {
+        Ok(body) => ([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Alive as long as the loop keeps ticking.
+async fn healthz(State(state): State<ServerState>) -> Response {
+    match state.health.is_alive() {
+        true => (StatusCode::OK, "ok").into_response(),
+        false => (StatusCode::SERVICE_UNAVAILABLE, "loop is not ticking").into_response(),
+    }
+}
+
+/// Ready when the RPC answers, the signer is loaded, the configuration has
+/// been validated and the signer can pay for gas.
+async fn readyz(State(state): State<ServerState>) -> Response {
+    let signer_address = state.health.signer_address();
+    let config_validated = state.health.is_config_validated();
+
+    let (balance_mist, error) = match signer_address {
+        Some(address) => match state
+            .sui_client
+            .coin_read_api()
+            .get_balance(address, None)
+            .await
+        {
+            Ok(balance) => (Some(balance.total_balance), None),
+            Err(e) => (None, Some(e.to_string())),
+        },
+        None => (None, Some("signer is not loaded".to_string())),
+    };
+    if let Some(balance_mist) = balance_mist {
+        state
+            .metrics
+            .signer_balance(balance_mist.min(u64::MAX as u128) as u64);
+    }
+
+    let gas_balance_ok = balance_mist.is_some_and(|b| b >= state.min_balance_mist as u128);
+    let readiness = Readiness {
+        ready: balance_mist.is_some() && config_validated && gas_balance_ok,
+        rpc_reachable: balance_mist.is_some(),
+        signer_loaded: signer_address.is_some(),
+        config_validated,
+        gas_balance_ok,
+        balance_mist,
+        error,
+    };
+    let status = match readiness.ready {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    (status, Json(readiness)).into_response()
+}
+
+/// Latest phase, action and error of the loop.
+async fn status(State(state): State<ServerState>) -> Response {
+    Json(state.health.status()).into_response()
+}
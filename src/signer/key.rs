@@ -0,0 +1,85 @@
+use std::{env, fs, path::Path};
+
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_sdk::types::{
+    base_types::SuiAddress,
+    crypto::{EncodeDecodeBase64, Signature, SuiKeyPair},
+    transaction::TransactionData,
+};
+
+use crate::error::{AnglerfishError, Result};
+
+use super::Signer;
+
+/// Signs with a private key held in memory, read from an environment variable
+/// or from a standalone key file.
+pub struct KeySigner {
+    keypair: SuiKeyPair,
+    address: SuiAddress,
+}
+
+impl KeySigner {
+    /// Reads the key from the environment variable `var`.
+    pub fn from_env(var: &str, address: Option<SuiAddress>) -> Result<Self> {
+        let encoded = env::var(var).map_err(|e| {
+            AnglerfishError::Signer(format!(
+                "Failed to read the signer key from ${}: {}",
+                var, e
+            ))
+        })?;
+        KeySigner::new(parse_keypair(&encoded)?, address)
+    }
+
+    /// Reads the key from the file at `path`.
+    pub fn from_file(path: Option<&Path>, address: Option<SuiAddress>) -> Result<Self> {
+        let path = path.ok_or_else(|| {
+            AnglerfishError::Signer("`key_path` is required by the file signer".to_string())
+        })?;
+        let encoded = fs::read_to_string(path).map_err(|e| {
+            AnglerfishError::Signer(format!(
+                "Failed to read the signer key from {:?}: {}",
+                path, e
+            ))
+        })?;
+        KeySigner::new(parse_keypair(&encoded)?, address)
+    }
+
+    /// Wraps the keypair. If `address` is given, it must be the address of the key.
+    pub fn new(keypair: SuiKeyPair, address: Option<SuiAddress>) -> Result<Self> {
+        let key_address = SuiAddress::from(&keypair.public());
+        match address {
+            Some(address) if address != key_address => Err(AnglerfishError::Signer(format!(
+                "Signer key belongs to {} but the configured address is {}",
+                key_address, address
+            ))),
+            _ => Ok(KeySigner {
+                keypair,
+                address: key_address,
+            }),
+        }
+    }
+}
+
+/// Parses a `suiprivkey` bech32 key, or a base64 key as stored in `sui.keystore`.
+fn parse_keypair(encoded: &str) -> Result<SuiKeyPair> {
+    let encoded = encoded.trim();
+    if encoded.starts_with("suiprivkey") {
+        SuiKeyPair::decode(encoded)
+            .map_err(|e| AnglerfishError::Signer(format!("Invalid suiprivkey: {}", e)))
+    } else {
+        SuiKeyPair::decode_base64(encoded)
+            .map_err(|e| AnglerfishError::Signer(format!("Invalid base64 key: {}", e)))
+    }
+}
+
+#[async_trait::async_trait]
+impl Signer for KeySigner {
+    fn address(&self) -> SuiAddress {
+        self.address
+    }
+
+    async fn sign(&self, tx_data: &TransactionData) -> Result<Signature> {
+        let intent_msg = IntentMessage::new(Intent::sui_transaction(), tx_data.clone());
+        Ok(Signature::new_secure(&intent_msg, &self.keypair))
+    }
+}
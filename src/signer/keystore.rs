@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use shared_crypto::intent::Intent;
+use sui_config::{PersistedConfig, SUI_CLIENT_CONFIG, SUI_KEYSTORE_FILENAME, sui_config_dir};
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
+use sui_sdk::{
+    sui_client_config::SuiClientConfig,
+    types::{base_types::SuiAddress, crypto::Signature, transaction::TransactionData},
+};
+use tracing::info;
+
+use crate::error::{AnglerfishError, Result};
+
+use super::Signer;
+
+/// Signs with a key of a Sui file based keystore (`sui.keystore`).
+pub struct KeystoreSigner {
+    keystore: FileBasedKeystore,
+    address: SuiAddress,
+}
+
+impl KeystoreSigner {
+    /// Loads the keystore at `keystore_path`, or the one of the Sui client
+    /// config directory.
+    ///
+    /// The signer is `address` if given, otherwise the active address of the
+    /// Sui client config. It must be present in the keystore. The Sui client
+    /// config is only read, never written back.
+    pub fn load(keystore_path: Option<PathBuf>, address: Option<SuiAddress>) -> Result<Self> {
+        let keystore_path = match keystore_path {
+            Some(keystore_path) => keystore_path,
+            None => sui_config_dir()
+                .map_err(|e| AnglerfishError::Signer(e.to_string()))?
+                .join(SUI_KEYSTORE_FILENAME),
+        };
+        info!(?keystore_path, "Loading keystore");
+
+        if !keystore_path.exists() {
+            return Err(AnglerfishError::Signer(format!(
+                "No keystore found at {:?}. Please create a new wallet using the command: `sui client new`",
+                keystore_path
+            )));
+        }
+        let keystore = FileBasedKeystore::new(&keystore_path).map_err(|e| {
+            AnglerfishError::Signer(format!(
+                "Failed to load the keystore {:?}: {}",
+                keystore_path, e
+            ))
+        })?;
+
+        let address = match address {
+            Some(address) => address,
+            None => active_address()?.ok_or_else(|| {
+                AnglerfishError::Signer(
+                    "No signer address selected. Set `address` in the `[signer]` section of the config or pass `--address`."
+                        .to_string(),
+                )
+            })?,
+        };
+
+        if !keystore.addresses().contains(&address) {
+            return Err(AnglerfishError::Signer(format!(
+                "Signer address {} not found in the keystore {:?}",
+                address, keystore_path
+            )));
+        }
+
+        Ok(KeystoreSigner { keystore, address })
+    }
+}
+
+/// Reads the active address of the Sui client config, if there is one.
+fn active_address() -> Result<Option<SuiAddress>> {
+    let wallet_conf = sui_config_dir()
+        .map_err(|e| AnglerfishError::Signer(e.to_string()))?
+        .join(SUI_CLIENT_CONFIG);
+    if !wallet_conf.exists() {
+        return Ok(None);
+    }
+    let client_config: SuiClientConfig = PersistedConfig::read(&wallet_conf)
+        .map_err(|e| AnglerfishError::Signer(format!("Failed to read {:?}: {}", wallet_conf, e)))?;
+    Ok(client_config.active_address)
+}
+
+#[async_trait::async_trait]
+impl Signer for KeystoreSigner {
+    fn address(&self) -> SuiAddress {
+        self.address
+    }
+
+    async fn sign(&self, tx_data: &TransactionData) -> Result<Signature> {
+        self.keystore
+            .sign_secure(&self.address, tx_data, Intent::sui_transaction())
+            .map_err(|e| {
+                AnglerfishError::Signer(format!("Failed to sign with the keystore: {}", e))
+            })
+    }
+}
@@ -0,0 +1,46 @@
+use sui_sdk::types::{base_types::SuiAddress, crypto::Signature, transaction::TransactionData};
+use tracing::info;
+
+use crate::{
+    config::{self, SignerBackend},
+    error::Result,
+};
+
+pub mod key;
+pub mod keystore;
+pub mod remote;
+
+/// Signer of the iterator transactions.
+///
+/// Backends only need to produce a signature of the transaction data under the
+/// `sui_transaction` intent for their address.
+#[async_trait::async_trait]
+pub trait Signer: Send + Sync {
+    fn address(&self) -> SuiAddress;
+
+    async fn sign(&self, tx_data: &TransactionData) -> Result<Signature>;
+}
+
+/// Builds the signer selected by the `[signer]` section of the configuration.
+pub fn load_signer(config: &config::Signer) -> Result<Box<dyn Signer>> {
+    let signer: Box<dyn Signer> = match config.backend {
+        SignerBackend::Keystore => Box::new(keystore::KeystoreSigner::load(
+            config.keystore_path.clone(),
+            config.address,
+        )?),
+        SignerBackend::Env => Box::new(key::KeySigner::from_env(&config.env_var, config.address)?),
+        SignerBackend::File => Box::new(key::KeySigner::from_file(
+            config.key_path.as_deref(),
+            config.address,
+        )?),
+        SignerBackend::Remote => Box::new(remote::RemoteSigner::new(
+            config.url.as_deref(),
+            config.address,
+            config.auth_token_env.as_deref(),
+        )?),
+    };
+
+    info!(address = %signer.address(), "Signer loaded");
+
+    Ok(signer)
+}
@@ -0,0 +1,262 @@
+use std::time::Duration;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_sdk::types::{
+    base_types::SuiAddress,
+    crypto::{Signature, SuiSignature, ToFromBytes},
+    transaction::TransactionData,
+};
+
+use crate::{
+    error::{AnglerfishError, Result},
+    helper::auth::{bearer_token_from_env, with_bearer_auth},
+};
+
+use super::Signer;
+
+/// Timeout of a signing request.
+pub const REMOTE_SIGNER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Signs through a remote signing service, so keys never live on the iterator host.
+///
+/// Protocol: `POST {url}/sign` with the JSON body
+/// `{"address": "0x..", "tx_bytes": "<base64 BCS TransactionData>"}`.
+/// The service signs the transaction data under the `sui_transaction` intent
+/// and answers `{"signature": "<base64 flag || signature || public key>"}`.
+/// The token read from `auth_token_env`, if set, authenticates the requests.
+pub struct RemoteSigner {
+    http_client: reqwest::Client,
+    url: String,
+    address: SuiAddress,
+    auth_token: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SignRequest {
+    address: SuiAddress,
+    tx_bytes: String,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: String,
+}
+
+fn signer_error(e: impl std::fmt::Display) -> AnglerfishError {
+    AnglerfishError::Signer(e.to_string())
+}
+
+/// A request which timed out or could not connect may succeed later.
+fn request_error(e: reqwest::Error) -> AnglerfishError {
+    let message = format!("Remote signer request failed: {}", e);
+    if e.is_timeout() || e.is_connect() {
+        AnglerfishError::SignerUnavailable(message)
+    } else {
+        AnglerfishError::Signer(message)
+    }
+}
+
+impl RemoteSigner {
+    pub fn new(
+        url: Option<&str>,
+        address: Option<SuiAddress>,
+        auth_token_env: Option<&str>,
+    ) -> Result<Self> {
+        let url = url.ok_or_else(|| {
+            AnglerfishError::Signer("`url` is required by the remote signer".to_string())
+        })?;
+        let address = address.ok_or_else(|| {
+            AnglerfishError::Signer("`address` is required by the remote signer".to_string())
+        })?;
+        let auth_token = bearer_token_from_env(auth_token_env).map_err(AnglerfishError::Signer)?;
+        let http_client = reqwest::Client::builder()
+            .timeout(REMOTE_SIGNER_TIMEOUT)
+            .build()
+            .map_err(signer_error)?;
+
+        Ok(RemoteSigner {
+            http_client,
+            url: url.trim_end_matches('/').to_string(),
+            address,
+            auth_token,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Signer for RemoteSigner {
+    fn address(&self) -> SuiAddress {
+        self.address
+    }
+
+    async fn sign(&self, tx_data: &TransactionData) -> Result<Signature> {
+        let request = SignRequest {
+            address: self.address,
+            tx_bytes: BASE64.encode(bcs::to_bytes(tx_data).map_err(signer_error)?),
+        };
+
+        let http_request = self
+            .http_client
+            .post(format!("{}/sign", self.url))
+            .json(&request);
+
+        let response = with_bearer_auth(http_request, self.auth_token.as_deref())
+            .send()
+            .await
+            .map_err(request_error)?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = format!("Remote signer answered {}", status);
+            return Err(match status {
+                StatusCode::TOO_MANY_REQUESTS
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT => AnglerfishError::SignerUnavailable(message),
+                _ => AnglerfishError::Signer(message),
+            });
+        }
+        let response: SignResponse = response.json().await.map_err(|e| {
+            AnglerfishError::Signer(format!("Remote signer returned an invalid response: {}", e))
+        })?;
+
+        let signature_bytes = BASE64.decode(response.signature).map_err(|e| {
+            AnglerfishError::Signer(format!("Remote signer returned invalid base64: {}", e))
+        })?;
+        let signature = Signature::from_bytes(&signature_bytes).map_err(|e| {
+            AnglerfishError::Signer(format!(
+                "Remote signer returned an invalid signature: {}",
+                e
+            ))
+        })?;
+
+        // Never submit a signature which does not belong to the signer address.
+        let intent_msg = IntentMessage::new(Intent::sui_transaction(), tx_data.clone());
+        signature
+            .verify_secure(&intent_msg, self.address, signature.scheme())
+            .map_err(|e| {
+                AnglerfishError::Signer(format!("Remote signer returned a wrong signature: {}", e))
+            })?;
+
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{Json, Router, http::StatusCode, routing::post};
+    use serde_json::{Value, json};
+    use sui_sdk::types::{
+        base_types::random_object_ref,
+        crypto::{AccountKeyPair, SuiKeyPair, get_key_pair},
+    };
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::signer::key::KeySigner;
+
+    fn keypair() -> SuiKeyPair {
+        let (_, keypair): (_, AccountKeyPair) = get_key_pair();
+        SuiKeyPair::Ed25519(keypair)
+    }
+
+    fn tx_data(sender: SuiAddress) -> TransactionData {
+        TransactionData::new_transfer_sui(
+            sender,
+            sender,
+            Some(1),
+            random_object_ref(),
+            10_000_000,
+            1_000,
+        )
+    }
+
+    /// Serves `/sign` on a local port, signing with `keypair`, or failing with
+    /// 503 if there is none. Returns the url of the service.
+    async fn serve(keypair: Option<SuiKeyPair>) -> String {
+        let signer = keypair.map(|keypair| Arc::new(KeySigner::new(keypair, None).unwrap()));
+        let app = Router::new().route(
+            "/sign",
+            post(move |Json(request): Json<Value>| {
+                let signer = signer.clone();
+                async move {
+                    let signer = signer.ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+                    let tx_bytes = BASE64
+                        .decode(request["tx_bytes"].as_str().unwrap())
+                        .unwrap();
+                    let tx_data: TransactionData = bcs::from_bytes(&tx_bytes).unwrap();
+                    let signature = signer.sign(&tx_data).await.unwrap();
+                    Ok::<_, StatusCode>(Json(json!({
+                        "signature": BASE64.encode(signature.as_ref()),
+                    })))
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    #[tokio::test]
+    async fn sign_accepts_the_signature_of_the_address() {
+        let keypair = keypair();
+        let address = SuiAddress::from(&keypair.public());
+        let url = serve(Some(keypair)).await;
+
+        let signer = RemoteSigner::new(Some(&url), Some(address), None).unwrap();
+        let signature = signer.sign(&tx_data(address)).await.unwrap();
+        assert_eq!(
+            SuiAddress::from(&signature.to_public_key().unwrap()),
+            address
+        );
+    }
+
+    #[tokio::test]
+    async fn sign_rejects_the_signature_of_another_key() {
+        let address = SuiAddress::from(&keypair().public());
+        let url = serve(Some(keypair())).await;
+
+        let signer = RemoteSigner::new(Some(&url), Some(address), None).unwrap();
+        match signer.sign(&tx_data(address)).await {
+            Err(AnglerfishError::Signer(message)) => {
+                assert!(message.contains("wrong signature"), "{}", message)
+            }
+            result => panic!("expected a wrong signature error, got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn sign_fails_on_error_status() {
+        let address = SuiAddress::from(&keypair().public());
+        let url = serve(None).await;
+
+        let signer = RemoteSigner::new(Some(&url), Some(address), None).unwrap();
+        match signer.sign(&tx_data(address)).await {
+            Err(AnglerfishError::SignerUnavailable(message)) => {
+                assert!(message.contains("503"), "{}", message)
+            }
+            result => panic!("expected an unavailable signer, got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn sign_fails_without_service() {
+        let address = SuiAddress::from(&keypair().public());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let signer = RemoteSigner::new(Some(&url), Some(address), None).unwrap();
+        let result = signer.sign(&tx_data(address)).await;
+        assert!(
+            matches!(result, Err(AnglerfishError::SignerUnavailable(_))),
+            "{:?}",
+            result
+        );
+    }
+}